@@ -42,13 +42,16 @@ impl<T> CalmUnwrap<T> for Option<T> {
 
 #[macro_export]
 /// Abandon the current execution with a message
+///
+/// Only for use at the top level. Commands should return a [`crate::errors::Error`] instead,
+/// so the program exits with the matching exit code
 macro_rules! abandon {
     () => {
         abandon!("Abandoned execution");
     };
 
     ($($arg:tt)*) => {{
-        eprintln!("{}", console::style(format_args!($($arg)*)).red());
+        eprintln!("{}", $crate::output::theme::Role::Error.style(format_args!($($arg)*)));
         std::process::exit(1);
     }};
 }
@@ -6,10 +6,14 @@ mod credits;
 mod debug;
 mod depends;
 mod describe;
+mod env;
 mod export;
 mod hook;
+mod import;
+mod lock;
 #[cfg(not(feature = "v2"))]
 mod outdated;
+mod rdepends;
 mod search;
 mod status;
 mod update;
@@ -20,7 +24,10 @@ use clap::Subcommand;
 use sfsu_macros::{Hooks, Runnable};
 use sprinkles::{config, contexts::ScoopContext};
 
-use crate::{abandon, output::colours::eprintln_yellow};
+use crate::output::{
+    format::{Format, Render},
+    theme::eprintln_styled,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct DeprecationWarning {
@@ -64,7 +71,6 @@ impl std::fmt::Display for DeprecationWarning {
     }
 }
 
-// TODO: Run command could return `impl Display` and print that itself
 pub trait Command {
     const BETA: bool = false;
     const NEEDS_ELEVATION: bool = false;
@@ -80,15 +86,16 @@ pub trait CommandRunner: Command {
         Self: Sized,
     {
         if let Some(deprecation_warning) = Self::DEPRECATED {
-            eprintln_yellow!("{deprecation_warning}\n");
+            eprintln_styled!(Warning, "{deprecation_warning}\n");
         }
 
         if Self::NEEDS_ELEVATION && !quork::root::is_root()? {
-            abandon!("This command requires elevation. Please run as an administrator.");
+            anyhow::bail!("This command requires elevation. Please run as an administrator.");
         }
 
         if Self::BETA {
-            eprintln_yellow!(
+            eprintln_styled!(
+                Warning,
                 "This command is in beta and may not work as expected. Please report any and all bugs you find!\n",
             );
         }
@@ -99,6 +106,46 @@ pub trait CommandRunner: Command {
 
 impl<T: Command> CommandRunner for T {}
 
+/// A command which returns its output as data, rather than printing it
+///
+/// The output is printed in the command's format by the blanket [`Command`] implementation,
+/// so queries can be reused by other commands, and tested without capturing stdout
+pub trait Query {
+    const DEPRECATED: Option<DeprecationWarning> = None;
+
+    /// The output of the query
+    type Output: Render;
+
+    /// The format to print the output in
+    fn format(&self) -> Format;
+
+    async fn query(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Self::Output>;
+
+    /// Check the output once it has been printed
+    ///
+    /// # Errors
+    /// - The output should end the program with a non-zero exit code
+    fn check(_output: &Self::Output) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: Query> Command for T {
+    const DEPRECATED: Option<DeprecationWarning> = T::DEPRECATED;
+
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        let format = self.format();
+        let output = self.query(ctx).await?;
+
+        format.render(&output)?;
+
+        T::check(&output)
+    }
+}
+
 #[derive(Debug, Clone, Subcommand, Hooks, Runnable)]
 pub enum Commands {
     App(app::Args),
@@ -130,10 +177,18 @@ pub enum Commands {
     #[cfg(not(feature = "v2"))]
     Outdated(outdated::Args),
     Depends(depends::Args),
+    #[no_hook]
+    Rdepends(rdepends::Args),
     Status(status::Args),
     #[cfg_attr(not(feature = "v2"), no_hook)]
     Update(update::Args),
     Export(export::Args),
+    #[no_hook]
+    Import(import::Args),
+    #[no_hook]
+    Lock(lock::Args),
+    #[no_hook]
+    Env(env::Args),
     Checkup(checkup::Args),
     #[cfg(feature = "download")]
     Cache(cache::Args),
@@ -3,7 +3,7 @@ use std::{fs::File, io::Read, sync::atomic::Ordering};
 use clap::Parser;
 use sprinkles::{contexts::ScoopContext, packages::reference::package};
 
-use crate::{abandon, COLOR_ENABLED};
+use crate::{errors::Error, COLOR_ENABLED};
 
 #[derive(Debug, Clone, Parser)]
 /// Show content of specified manifest
@@ -17,7 +17,9 @@ impl super::Command for Args {
         let manifests = self.package.list_manifest_paths(ctx);
 
         if manifests.is_empty() {
-            abandon!("No manifests found for {}", self.package);
+            return Err(
+                Error::not_found(format!("No manifests found for {}", self.package)).into(),
+            );
         }
 
         let manifest = &manifests[0];
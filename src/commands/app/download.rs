@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use anyhow::Context;
 use clap::Parser;
 
 use sprinkles::{
@@ -11,7 +12,7 @@ use sprinkles::{
     Architecture,
 };
 
-use crate::{abandon, output::colours::eprintln_yellow};
+use crate::{errors::Error, output::theme::eprintln_styled};
 
 #[derive(Debug, Clone, Parser)]
 /// Download the specified app.
@@ -34,11 +35,12 @@ impl super::Command for Args {
 
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         if self.packages.is_empty() {
-            abandon!("No packages provided")
+            return Err(Error::invalid_input("No packages provided").into());
         }
 
         if self.no_hash_check {
-            eprintln_yellow!(
+            eprintln_styled!(
+                Warning,
                 "Hash check has been disabled! This may allow modified files to be downloaded"
             );
         }
@@ -52,10 +54,10 @@ impl super::Command for Args {
             futures::future::try_join_all(self.packages.into_iter().map(|package| {
                 let mp = mp.clone();
                 async move {
-                    let manifest = match package.manifest(ctx).await {
-                        Ok(manifest) => manifest,
-                        Err(e) => abandon!("\rFailed to generate manifest: {e}"),
-                    };
+                    let manifest = package
+                        .manifest(ctx)
+                        .await
+                        .context("Failed to generate manifest")?;
 
                     let dl = Handle::open_manifest(ctx.cache_path(), &manifest, self.arch)?;
 
@@ -66,7 +68,10 @@ impl super::Command for Args {
                                 Ok(dl) => anyhow::Ok(dl),
                                 Err(e) => match e {
                                     sprinkles::cache::Error::ErrorCode(status) => {
-                                        abandon!("Found {status} error while downloading")
+                                        Err(Error::network(format!(
+                                            "Found {status} error while downloading"
+                                        ))
+                                        .into())
                                     }
                                     _ => Err(e.into()),
                                 },
@@ -103,7 +108,7 @@ impl super::Command for Args {
                     eprintln!("\r🔒 Hash matched: {actual_hash}");
                 } else {
                     eprintln!();
-                    abandon!(
+                    anyhow::bail!(
                         "🔓 Hash mismatch: expected {actual_hash}, found {}",
                         result.computed_hash.no_prefix()
                     );
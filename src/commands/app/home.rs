@@ -1,7 +1,7 @@
 use clap::Parser;
 use sprinkles::{contexts::ScoopContext, packages::reference::package};
 
-use crate::abandon;
+use crate::errors::Error;
 
 #[derive(Debug, Clone, Parser)]
 /// Opens the app homepage
@@ -15,10 +15,10 @@ impl super::Command for Args {
         let manifest = self
             .package
             .first(ctx)
-            .ok_or(anyhow::anyhow!("Package not found"))?;
+            .ok_or_else(|| Error::not_found("Package not found"))?;
 
         let Some(homepage) = manifest.homepage else {
-            abandon!("No homepage found for package");
+            return Err(Error::not_found("No homepage found for package").into());
         };
 
         open::that_detached(homepage)?;
@@ -2,6 +2,7 @@ use clap::Parser;
 use itertools::Itertools;
 
 use sprinkles::{
+    buckets::Bucket,
     contexts::ScoopContext,
     packages::{
         models::manifest::{NestedArray, SingleOrArray, StringArray},
@@ -12,10 +13,10 @@ use sprinkles::{
 };
 
 use crate::{
-    abandon,
-    models::info::Package,
-    output::structured::vertical::VTable,
-    wrappers::{bool::NicerBool, time::NicerTime},
+    errors::Error,
+    models::{index::BucketIndex, info::Package},
+    output::{format::Format, structured::vertical::VTable},
+    wrappers::{bool::NicerBool, time::NicerTime, version::Version},
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -43,6 +44,9 @@ pub struct Args {
     #[clap(from_global)]
     json: bool,
 
+    #[clap(from_global)]
+    format: Option<Format>,
+
     #[clap(from_global)]
     verbose: bool,
 }
@@ -56,13 +60,29 @@ impl super::Command for Args {
             }
         }
 
-        let manifests = self.package.list_manifests(ctx).await?;
+        let manifests = if let Some((name, buckets)) = self.indexed_buckets(ctx) {
+            buckets
+                .into_iter()
+                .filter_map(|bucket| {
+                    let bucket = Bucket::from_name(ctx, bucket).ok()?;
+                    bucket.get_manifest(&name).ok()
+                })
+                .collect()
+        } else {
+            self.package.list_manifests(ctx).await?
+        };
 
         if manifests.is_empty() {
-            abandon!("No package found with the name \"{}\"", self.package);
+            return Err(Error::not_found(format!(
+                "No package found with the name \"{}\"",
+                self.package
+            ))
+            .into());
         }
 
-        if manifests.len() > 1 && !self.single {
+        let format = Format::resolve(self.format, self.json);
+
+        if manifests.len() > 1 && !self.single && format.is_table() {
             println!(
                 "Found {} packages, matching \"{}\":",
                 manifests.len(),
@@ -73,21 +93,28 @@ impl super::Command for Args {
         let manifests = if self.single {
             let latest = manifests
                 .into_iter()
-                .max_by(|a_manifest, b_manifest| {
-                    semver::Version::try_from(&a_manifest.version)
-                        .and_then(|a_version| {
-                            Ok(a_version.cmp(&semver::Version::try_from(&b_manifest.version)?))
-                        })
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                }).expect("something went terribly wrong (no manifests found even though we just checked for manifests)");
+                .max_by_key(|manifest| Version::new(&manifest.version))
+                .expect("something went terribly wrong (no manifests found even though we just checked for manifests)");
 
             vec![latest]
         } else {
             manifests
         };
 
-        for manifest in manifests {
-            self.print_manifest(ctx, manifest, Architecture::ARCH)?;
+        let packages = manifests
+            .into_iter()
+            .map(|manifest| self.package(ctx, manifest, Architecture::ARCH))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if format.is_table() {
+            for package in &packages {
+                let value = serde_json::to_value(package)?;
+                println!("{}", VTable::new(&value));
+            }
+        } else if let [package] = packages.as_slice() {
+            format.print_value(package)?;
+        } else {
+            format.print_rows(&packages, None)?;
         }
 
         Ok(())
@@ -95,12 +122,24 @@ impl super::Command for Args {
 }
 
 impl Args {
-    fn print_manifest(
+    /// Find the buckets containing the package using the search index, if the package has no bucket
+    fn indexed_buckets(&self, ctx: &impl ScoopContext) -> Option<(String, Vec<String>)> {
+        if self.package.bucket().is_some() {
+            return None;
+        }
+
+        let name = self.package.name()?.to_string();
+        let buckets = BucketIndex::locate(ctx, &name).ok()?;
+
+        Some((name, buckets))
+    }
+
+    fn package(
         &self,
         ctx: &impl ScoopContext,
         manifest: Manifest,
         arch: Architecture,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Package> {
         let install_path = {
             let __install_path = ctx.apps_path().join(unsafe { manifest.name() });
 
@@ -171,15 +210,6 @@ impl Args {
             }),
         };
 
-        let value = serde_json::to_value(pkg_info)?;
-        if self.json {
-            let output = serde_json::to_string_pretty(&value)?;
-            println!("{output}");
-        } else {
-            let table = VTable::new(&value);
-            println!("{table}");
-        }
-
-        Ok(())
+        Ok(pkg_info)
     }
 }
@@ -3,17 +3,36 @@ use rayon::prelude::*;
 
 use sprinkles::contexts::ScoopContext;
 
-use crate::{models::min::Info, output::structured::Structured};
+#[cfg(not(feature = "v2"))]
+use crate::patterns::{self, Pattern};
+use crate::{models::min::Info, output::format::Format};
 
 #[derive(Debug, Clone, Parser)]
 /// List all installed packages
 pub struct Args {
     #[cfg(not(feature = "v2"))]
     #[clap(
-        help = format!("The pattern to search for (can be a regex). {}", console::style("DEPRECATED: Use sfsu search --installed. Will be removed in v2").yellow())
+        help = format!("The glob pattern to filter packages by. A pattern without wildcards matches anywhere in the name. {}", console::style("DEPRECATED: Use sfsu search --installed. Will be removed in v2").yellow())
     )]
     pattern: Option<String>,
 
+    #[cfg(not(feature = "v2"))]
+    #[clap(
+        long,
+        requires = "pattern",
+        help = "Treat the pattern as a regex, using Rust Regex syntax"
+    )]
+    regex: bool,
+
+    #[cfg(not(feature = "v2"))]
+    #[clap(
+        short,
+        long,
+        requires = "pattern",
+        help = "Whether or not the pattern should match case-sensitively"
+    )]
+    case_sensitive: bool,
+
     #[clap(short, long, help = "The bucket to exclusively list packages in")]
     bucket: Option<String>,
 
@@ -25,6 +44,9 @@ pub struct Args {
 
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 #[derive(Debug, Copy, Clone, ValueEnum)]
@@ -40,6 +62,19 @@ impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let mut outputs = Info::list_installed(ctx, self.bucket.as_ref())?;
 
+        #[cfg(not(feature = "v2"))]
+        if let Some(pattern) = &self.pattern {
+            let pattern = Pattern::new(
+                pattern,
+                patterns::Options::new()
+                    .regex(self.regex)
+                    .case_sensitive(self.case_sensitive)
+                    .partial(true),
+            )?;
+
+            outputs.retain(|info| pattern.is_match(&info.name));
+        }
+
         outputs.par_sort_by(|a, b| match self.sort_by {
             SortBy::Name => a.name.cmp(&b.name),
             SortBy::Version => a.version.cmp(&b.version),
@@ -52,26 +87,15 @@ impl super::Command for Args {
             outputs.reverse();
         }
 
-        if self.json {
-            let output_json = serde_json::to_string_pretty(&outputs)?;
+        let format = Format::resolve(self.format, self.json);
 
-            println!("{output_json}");
-        } else {
-            if outputs.is_empty() {
-                println!("No packages found.");
-                return Ok(());
-            }
-
-            let values = outputs
-                .into_par_iter()
-                .map(serde_json::to_value)
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let outputs = Structured::new(&values).with_max_length(30);
-
-            print!("{outputs}");
+        if format.is_table() && outputs.is_empty() {
+            println!("No packages found.");
+            return Ok(());
         }
 
+        format.print_rows(&outputs, Some(30))?;
+
         Ok(())
     }
 }
@@ -12,10 +12,19 @@ use sprinkles::{
     progress::{indicatif::ProgressBar, style},
 };
 
-use crate::output::colours::{eprintln_yellow, yellow};
+use crate::{
+    models::plan::{Operation, Plan},
+    output::{
+        format::Format,
+        theme::{eprintln_styled, styled},
+    },
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Purge package's persist folder
+///
+/// Use the global `--dry-run` flag to list the folders which would be purged.
+/// Note that `-d` is short for the global `--debug` flag, and does not perform a dry run
 pub struct Args {
     #[clap(help = "The package to purge")]
     apps: Vec<package::Reference>,
@@ -23,12 +32,14 @@ pub struct Args {
     #[clap(from_global)]
     assume_yes: bool,
 
-    #[clap(
-        short,
-        long,
-        help = "Print what would be done, but don't actually do anything"
-    )]
+    #[clap(from_global)]
     dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
@@ -43,20 +54,29 @@ impl super::Command for Args {
         let (apps, missing_apps) = collect_references(ctx, refs);
 
         if !missing_apps.is_empty() {
-            eprintln_yellow!("Could not find the following apps:");
+            eprintln_styled!(Warning, "Could not find the following apps:");
             for reference in missing_apps {
-                eprintln_yellow!("- {}", reference);
+                eprintln_styled!(Warning, "- {}", reference);
             }
             eprintln!();
         }
 
         if apps.is_empty() {
-            eprintln_yellow!("No apps found");
+            eprintln_styled!(Warning, "No apps found");
             return Ok(());
         }
 
         let app_paths = AppPaths::new(ctx, apps);
 
+        if self.dry_run {
+            let plan = app_paths
+                .values()
+                .map(|(_, path)| Operation::RemoveDir { path: path.clone() })
+                .collect::<Plan>();
+
+            return Format::resolve(self.format, self.json).render(&plan);
+        }
+
         eprintln!(
             "Purging persist folders for {} {}:",
             if purging_uninstalled {
@@ -79,7 +99,8 @@ impl super::Command for Args {
         if !self.assume_yes
             && !Confirm::new()
                 .with_prompt(
-                    yellow!(
+                    styled!(
+                        Warning,
                         "Are you sure you want to purge the persist folder for {}?",
                         if app_paths.len() == 1 {
                             "this app".to_string()
@@ -99,7 +120,8 @@ impl super::Command for Args {
                 .values()
                 .any(|(app, _)| app.is_installed(ctx, None)) && !Confirm::new()
                 .with_prompt(
-                    yellow!(
+                    styled!(
+                        Warning,
                         "Some apps are installed. This could cause issues when running the app. Are you sure you want to continue?")
                     .to_string(),
                 )
@@ -111,11 +133,11 @@ impl super::Command for Args {
         if app_paths.len() == 1 {
             let (app, path) = app_paths.values().next().unwrap();
 
-            eprintln_yellow!("Purging persist folder for {}", unsafe { app.name() });
+            eprintln_styled!(Warning, "Purging persist folder for {}", unsafe {
+                app.name()
+            });
 
-            if !self.dry_run {
-                std::fs::remove_dir_all(path)?;
-            }
+            std::fs::remove_dir_all(path)?;
         } else {
             let pb = ProgressBar::new(app_paths.len() as u64).with_style(style(None, None));
 
@@ -125,11 +147,7 @@ impl super::Command for Args {
                 }));
                 pb.inc(1);
 
-                if self.dry_run {
-                    std::thread::sleep(std::time::Duration::from_millis(100));
-                } else {
-                    std::fs::remove_dir_all(persist_path)?;
-                }
+                std::fs::remove_dir_all(persist_path)?;
             }
         }
 
@@ -159,7 +177,7 @@ impl AppPaths {
                 }
 
                 if !persist_path.exists() {
-                    eprintln_yellow!("Persist folder does not exist for {}", unsafe {
+                    eprintln_styled!(Warning, "Persist folder does not exist for {}", unsafe {
                         app.name()
                     });
                     continue;
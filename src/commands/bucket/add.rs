@@ -1,12 +1,19 @@
-use std::time::Duration;
+use std::{io::Write, process::Stdio, time::Duration};
 
-use tokio::process::Command;
+use tokio::{io::AsyncReadExt, process::Command};
 
 use anyhow::Context;
 use clap::Parser;
-use sprinkles::{contexts::ScoopContext, progress::indicatif};
+use sprinkles::{buckets::Bucket, contexts::ScoopContext, progress::indicatif};
 
-use crate::{abandon, calm_panic::CalmUnwrap};
+use crate::{
+    errors::{Error, Kind},
+    models::{
+        index::BucketIndex,
+        plan::{Operation, Plan},
+    },
+    output::{format::Format, theme::eprintln_styled},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Add a bucket
@@ -19,75 +26,235 @@ pub struct Args {
 
     #[clap(from_global)]
     disable_git: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
-        let repo_url = self
-            .repo
-            .clone()
-            .context("No repo provided")
-            .unwrap_or_else(|_| {
-                let known_buckets = ctx.known_buckets();
-
-                if let Some(url) = known_buckets.get(&self.name) {
-                    (*url).to_string()
-                } else {
-                    abandon!(
-                        "No bucket found with the name \"{}\". Try passing the url as well",
-                        self.name
-                    )
-                }
-            });
+        let repo_url = if let Some(repo) = self.repo.clone() {
+            repo
+        } else {
+            let known_buckets = ctx.known_buckets();
+
+            let Some(url) = known_buckets.get(&self.name) else {
+                return Err(Error::not_found(format!(
+                    "No bucket found with the name \"{}\". Try passing the url as well",
+                    self.name
+                ))
+                .into());
+            };
+
+            (*url).to_string()
+        };
 
         let dest_path = ctx.buckets_path().join(&self.name);
 
         if dest_path.exists() {
-            abandon!("Bucket {name} already exists. Remove it first if you want to add it again: `sfsu bucket rm {name}`", name = self.name);
+            return Err(Error::invalid_input(format!("Bucket {name} already exists. Remove it first if you want to add it again: `sfsu bucket rm {name}`", name = self.name)).into());
+        }
+
+        if self.dry_run {
+            let plan = Plan::from_iter([
+                Operation::GitClone {
+                    url: repo_url,
+                    path: dest_path,
+                    branch: None,
+                },
+                Operation::WriteFile {
+                    path: BucketIndex::path(ctx, &self.name),
+                },
+            ]);
+
+            return Format::resolve(self.format, self.json).render(&plan);
         }
 
-        if self.disable_git {
-            let spinner = indicatif::ProgressBar::new_spinner();
-            spinner.set_message("Cloning repository");
-            spinner.enable_steady_tick(Duration::from_millis(100));
+        clone(ctx, &self.name, &repo_url, None, self.disable_git).await
+    }
+}
 
-            sprinkles::git::clone::clone(
-                &repo_url,
-                dest_path,
-                sprinkles::git::clone::progress::Discard,
-            )?;
+/// Clone a bucket into the buckets directory, optionally checking out a branch, and build its search index
+///
+/// Uses the `git` executable, or the built-in git implementation if `disable_git` is set
+///
+/// # Errors
+/// - git could not be found
+/// - A branch was provided with `disable_git`, which the built-in implementation does not support
+/// - The bucket's directory already exists
+/// - Cloning the repository failed. See [`clone_error`] for how failures are classified
+pub async fn clone(
+    ctx: &impl ScoopContext,
+    name: &str,
+    repo_url: &str,
+    branch: Option<&str>,
+    disable_git: bool,
+) -> anyhow::Result<()> {
+    let dest_path = ctx.buckets_path().join(name);
 
-            spinner.finish_with_message("✅ Repository cloned");
-        } else {
-            let git_path = sprinkles::git::which().calm_expect("git not found");
-
-            let exit_status = Command::new(git_path)
-                .current_dir(ctx.buckets_path())
-                .arg("clone")
-                .arg(repo_url)
-                .arg(self.name)
-                .spawn()?
-                .wait_with_output()
-                .await?;
-
-            match exit_status.status.code() {
-                Some(0) => {}
-                Some(code) => {
-                    return Err(anyhow::anyhow!(
-                        "git exited with code {}.\nOutput:\n{}",
-                        code,
-                        String::from_utf8_lossy(&exit_status.stdout)
-                    ))
-                }
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "git exited without a status code.\nOutput:\n{}",
-                        String::from_utf8_lossy(&exit_status.stdout)
-                    ))
+    if dest_path.exists() {
+        return Err(Error::invalid_input(format!(
+            "Bucket directory {} already exists",
+            dest_path.display()
+        ))
+        .into());
+    }
+
+    if disable_git {
+        if branch.is_some() {
+            return Err(Error::invalid_input(
+                "Cloning a specific branch requires git. Try again without `--disable-git`",
+            )
+            .into());
+        }
+
+        let spinner = indicatif::ProgressBar::new_spinner();
+        spinner.set_message("Cloning repository");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        sprinkles::git::clone::clone(
+            repo_url,
+            dest_path,
+            sprinkles::git::clone::progress::Discard,
+        )
+        .map_err(|error| clone_error(&format!("{error:#}")))?;
+
+        spinner.finish_with_message("✅ Repository cloned");
+    } else {
+        let git_path = sprinkles::git::which().context("git not found")?;
+
+        let mut command = Command::new(git_path);
+        command.current_dir(ctx.buckets_path()).arg("clone");
+
+        if let Some(branch) = branch {
+            command.args(["--branch", branch]);
+        }
+
+        // git only reports progress to a terminal unless asked to
+        let mut child = command
+            .arg("--progress")
+            .arg(repo_url)
+            .arg(name)
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Forward git's progress, and keep its output to classify failures
+        let mut output = vec![];
+        if let Some(mut stderr) = child.stderr.take() {
+            let mut buf = [0; 1024];
+
+            loop {
+                let read = stderr.read(&mut buf).await?;
+                if read == 0 {
+                    break;
                 }
+
+                std::io::stderr().write_all(&buf[..read])?;
+                output.extend_from_slice(&buf[..read]);
             }
-        };
+        }
+
+        let exit_status = child.wait().await?;
+
+        if !exit_status.success() {
+            return Err(clone_error(&String::from_utf8_lossy(&output)).into());
+        }
+    };
+
+    // The bucket is usable without an index, so failing to build one is not fatal
+    if let Err(error) = Bucket::from_name(ctx, name)
+        .map_err(anyhow::Error::from)
+        .and_then(|bucket| BucketIndex::refresh(ctx, &bucket))
+    {
+        eprintln_styled!(
+            Warning,
+            "Failed to build the search index for {name}: {error}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Classify a failed clone by its error output, from either `git` or the built-in implementation
+///
+/// Bad urls and existing directories are invalid input, filesystem failures are io errors,
+/// and only transport failures are network errors
+fn clone_error(output: &str) -> Error {
+    const INVALID_INPUT: &[&str] = &[
+        "already exists",
+        "not found",
+        "does not appear to be a git repository",
+        "unable to find remote helper",
+        "unsupported url protocol",
+        "is not supported",
+        "invalid url",
+    ];
+    const IO: &[&str] = &[
+        "could not create",
+        "failed to make directory",
+        "read-only file system",
+        "no space left",
+    ];
+    const NETWORK: &[&str] = &[
+        "could not resolve",
+        "failed to resolve address",
+        "unable to access",
+        "failed to connect",
+        "could not read from remote",
+        "timed out",
+        "early eof",
+        "rpc failed",
+        "connection",
+    ];
+
+    let lowercase = output.to_lowercase();
+    let matches = |patterns: &[&str]| patterns.iter().any(|pattern| lowercase.contains(pattern));
+
+    let kind = if matches(INVALID_INPUT) {
+        Kind::InvalidInput
+    } else if matches(IO) {
+        Kind::Io
+    } else if matches(NETWORK) {
+        Kind::Network
+    } else {
+        Kind::General
+    };
+
+    Error::new(
+        kind,
+        format!("Failed to clone the bucket:\n{}", output.trim()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_error() {
+        let kind = |output| Error::kind_of(&clone_error(output).into());
 
-        Ok(())
+        assert_eq!(
+            kind("fatal: destination path 'extras' already exists and is not an empty directory."),
+            Kind::InvalidInput
+        );
+        assert_eq!(
+            kind("remote: Repository not found.\nfatal: repository 'https://github.com/a/b/' not found"),
+            Kind::InvalidInput
+        );
+        assert_eq!(
+            kind("fatal: could not create work tree dir 'extras': Permission denied"),
+            Kind::Io
+        );
+        assert_eq!(
+            kind("fatal: unable to access 'https://github.com/a/b/': Could not resolve host: github.com"),
+            Kind::Network
+        );
     }
 }
@@ -3,6 +3,8 @@ use itertools::Itertools;
 use serde::Serialize;
 use sprinkles::contexts::ScoopContext;
 
+use crate::output::format::Format;
+
 #[derive(Debug, Clone, Serialize)]
 struct KnownBucket {
     name: String,
@@ -14,6 +16,9 @@ struct KnownBucket {
 pub struct Args {
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
@@ -28,14 +33,7 @@ impl super::Command for Args {
             })
             .collect_vec();
 
-        if self.json {
-            let output = serde_json::to_string_pretty(&known_buckets)?;
-            println!("{output}");
-        } else {
-            let structured = crate::output::structured::Structured::new(&known_buckets);
-
-            println!("{structured}");
-        }
+        Format::resolve(self.format, self.json).print_rows(&known_buckets, None)?;
 
         Ok(())
     }
@@ -5,13 +5,16 @@ use rayon::prelude::*;
 use serde::Serialize;
 use sprinkles::{buckets::Bucket, contexts::ScoopContext};
 
-use crate::{output, wrappers::time::NicerTime};
+use crate::{output::format::Format, wrappers::time::NicerTime};
 
 #[derive(Debug, Clone, Parser)]
 /// List all installed buckets
 pub struct Args {
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -58,14 +61,7 @@ impl super::Command for Args {
             buckets
         };
 
-        if self.json {
-            let output = serde_json::to_string_pretty(&buckets)?;
-            println!("{output}");
-        } else {
-            let structured = output::structured::Structured::new(&buckets);
-
-            println!("{structured}");
-        }
+        Format::resolve(self.format, self.json).print_rows(&buckets, None)?;
 
         Ok(())
     }
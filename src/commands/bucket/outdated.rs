@@ -4,7 +4,10 @@ use sprinkles::{buckets::Bucket, contexts::ScoopContext};
 
 use crate::{
     commands::{self, DeprecationMessage, DeprecationWarning},
-    output::sectioned::{Children, Section},
+    output::{
+        format::Format,
+        sectioned::{Children, Section},
+    },
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -12,6 +15,9 @@ use crate::{
 pub struct Args {
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl commands::Command for Args {
@@ -36,12 +42,12 @@ impl commands::Command for Args {
             })
             .collect_vec();
 
-        if outdated_buckets.is_empty() {
-            eprintln!("All buckets up to date.");
-        } else if self.json {
-            let json = serde_json::to_string_pretty(&outdated_buckets)?;
+        let format = Format::resolve(self.format, self.json);
 
-            println!("{json}");
+        if !format.is_table() {
+            format.print_value(&outdated_buckets)?;
+        } else if outdated_buckets.is_empty() {
+            eprintln!("All buckets up to date.");
         } else {
             let title = format!("{} outdated buckets:", outdated_buckets.len());
 
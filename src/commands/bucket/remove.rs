@@ -2,7 +2,11 @@ use clap::Parser;
 use dialoguer::Confirm;
 use sprinkles::contexts::ScoopContext;
 
-use crate::{abandon, output::colours::yellow};
+use crate::{
+    errors::Error,
+    models::plan::{Operation, Plan},
+    output::{format::Format, theme::styled},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Remove a bucket
@@ -12,6 +16,15 @@ pub struct Args {
 
     #[clap(from_global)]
     assume_yes: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
@@ -19,12 +32,20 @@ impl super::Command for Args {
         let path = ctx.buckets_path().join(&self.name);
 
         if !path.exists() {
-            abandon!("Bucket \"{}\" is not installed", self.name);
+            return Err(
+                Error::not_found(format!("Bucket \"{}\" is not installed", self.name)).into(),
+            );
+        }
+
+        if self.dry_run {
+            let plan = Plan::from_iter([Operation::RemoveDir { path }]);
+
+            return Format::resolve(self.format, self.json).render(&plan);
         }
 
         if self.name == "main" && !self.assume_yes {
             Confirm::new()
-            .with_prompt(yellow!("You probably don't want to delete the main bucket. Are you sure you want to continue?").to_string())
+            .with_prompt(styled!(Warning, "You probably don't want to delete the main bucket. Are you sure you want to continue?").to_string())
             .default(false)
             .interact()?;
         }
@@ -5,7 +5,10 @@ use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::InstallManife
 
 use crate::{
     commands,
-    output::sectioned::{Children, Section},
+    output::{
+        format::Format,
+        sectioned::{Children, Section},
+    },
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -13,6 +16,9 @@ use crate::{
 pub struct Args {
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl commands::Command for Args {
@@ -34,9 +40,10 @@ impl commands::Command for Args {
             })
             .collect::<Vec<_>>();
 
-        if self.json {
-            let output = serde_json::to_string_pretty(&unused_buckets)?;
-            println!("{output}");
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
+            format.print_value(&unused_buckets)?;
         } else {
             let unused_buckets = Children::from(unused_buckets);
             if let Children::None = unused_buckets {
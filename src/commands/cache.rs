@@ -2,7 +2,6 @@ use std::{os::windows::fs::MetadataExt, path::PathBuf};
 
 use anyhow::Context;
 use clap::{Parser, Subcommand};
-use regex::Regex;
 use serde::Serialize;
 use sfsu_macros::Runnable;
 use sprinkles::{config, contexts::ScoopContext};
@@ -11,7 +10,13 @@ use tokio::task::JoinSet;
 mod list;
 mod remove;
 
-use crate::{abandon, commands::CommandRunner, wrappers::sizes::Size};
+use crate::{
+    commands::CommandRunner,
+    errors::Error,
+    output::format::Format,
+    patterns::{self, Pattern},
+    wrappers::sizes::Size,
+};
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 struct CacheEntry {
@@ -27,13 +32,14 @@ impl CacheEntry {
     pub async fn match_paths(
         ctx: &impl ScoopContext,
         patterns: &[String],
+        options: patterns::Options,
     ) -> anyhow::Result<Vec<Self>> {
         let cache_path = ctx.cache_path();
 
         let patterns = patterns
             .iter()
-            .filter_map(|pattern| Regex::new(&format!("^{pattern}#")).ok())
-            .collect::<Vec<_>>();
+            .map(|pattern| Pattern::new(pattern, options))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let mut set = JoinSet::new();
         let mut dir = tokio::fs::read_dir(cache_path).await?;
@@ -42,7 +48,10 @@ impl CacheEntry {
             let file_name = entry.file_name();
             let file_name = file_name.to_string_lossy();
 
-            if !patterns.iter().any(|pattern| pattern.is_match(&file_name)) {
+            // Cache entries are named `app#version#url`, and patterns only match the app name
+            let app_name = file_name.split('#').next().unwrap_or_default();
+
+            if !patterns.iter().any(|pattern| pattern.is_match(app_name)) {
                 continue;
             }
 
@@ -84,7 +93,7 @@ impl CacheEntry {
         };
 
         if cache_entries.is_empty() {
-            abandon!("No cache entries found");
+            return Err(Error::not_found("No cache entries found").into());
         }
 
         cache_entries.sort();
@@ -93,6 +102,12 @@ impl CacheEntry {
     }
 }
 
+fn pattern_options(regex: bool, case_sensitive: bool) -> patterns::Options {
+    patterns::Options::new()
+        .regex(regex)
+        .case_sensitive(case_sensitive)
+}
+
 #[derive(Debug, Clone, Subcommand, Runnable)]
 enum Commands {
     #[clap(alias = "show", alias = "ls")]
@@ -110,12 +125,30 @@ pub struct Args {
     #[clap(
         global = true,
         help = "Glob pattern(s) for apps to show cache entries for",
-        default_value = ".*?"
+        default_value = "*"
     )]
     apps: Vec<String>,
 
+    #[clap(
+        long,
+        global = true,
+        help = "Treat the app patterns as regexes, using Rust Regex syntax"
+    )]
+    regex: bool,
+
+    #[clap(
+        short,
+        long,
+        global = true,
+        help = "Whether or not the app patterns should match case-sensitively"
+    )]
+    case_sensitive: bool,
+
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
@@ -125,7 +158,10 @@ impl super::Command for Args {
     ) -> Result<(), anyhow::Error> {
         let command = self.command.unwrap_or(Commands::List(list::Args {
             json: self.json,
+            format: self.format,
             apps: self.apps,
+            regex: self.regex,
+            case_sensitive: self.case_sensitive,
         }));
 
         command.run(ctx).await
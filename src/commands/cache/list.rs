@@ -3,11 +3,11 @@ use sprinkles::contexts::ScoopContext;
 
 use crate::{
     commands::Command,
-    output::{colours::eprintln_bright_yellow, structured::Structured},
+    output::{format::Format, theme::eprintln_styled},
     wrappers::sizes::Size,
 };
 
-use super::CacheEntry;
+use super::{pattern_options, CacheEntry};
 
 #[derive(Debug, Clone, Parser)]
 /// List cache entries
@@ -15,29 +15,40 @@ pub struct Args {
     #[clap(from_global)]
     pub apps: Vec<String>,
 
+    #[clap(from_global)]
+    pub regex: bool,
+
+    #[clap(from_global)]
+    pub case_sensitive: bool,
+
     #[clap(from_global)]
     pub json: bool,
+
+    #[clap(from_global)]
+    pub format: Option<Format>,
 }
 
 impl Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let cache_entries = CacheEntry::match_paths(ctx, &self.apps).await?;
+        let cache_entries = CacheEntry::match_paths(
+            ctx,
+            &self.apps,
+            pattern_options(self.regex, self.case_sensitive),
+        )
+        .await?;
 
         let total_size = cache_entries
             .iter()
             .fold(Size::new(0), |acc, entry| acc + entry.size);
 
-        eprintln_bright_yellow!("Total: {} files, {total_size}", cache_entries.len());
-
-        let values = cache_entries
-            .into_iter()
-            .map(serde_json::to_value)
-            .collect::<Result<Vec<_>, _>>()?;
+        eprintln_styled!(
+            Highlight,
+            "Total: {} files, {total_size}",
+            cache_entries.len()
+        );
 
         // TODO: Figure out max length so urls aren't truncated unless they need to be
-        let data = Structured::new(&values).with_max_length(50);
-
-        println!("{data}");
+        Format::resolve(self.format, self.json).print_rows(&cache_entries, Some(50))?;
 
         Ok(())
     }
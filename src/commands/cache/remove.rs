@@ -1,20 +1,56 @@
 use clap::Parser;
 use sprinkles::contexts::ScoopContext;
 
-use crate::{commands::Command, output::colours::eprintln_bright_yellow, wrappers::sizes::Size};
+use crate::{
+    commands::Command,
+    models::plan::{Operation, Plan},
+    output::{format::Format, theme::eprintln_styled},
+    wrappers::sizes::Size,
+};
 
-use super::CacheEntry;
+use super::{pattern_options, CacheEntry};
 
 #[derive(Debug, Clone, Parser)]
 /// Remove cache entries
 pub struct Args {
     #[clap(from_global)]
     apps: Vec<String>,
+
+    #[clap(from_global)]
+    regex: bool,
+
+    #[clap(from_global)]
+    case_sensitive: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let cache_entries = CacheEntry::match_paths(ctx, &self.apps).await?;
+        let cache_entries = CacheEntry::match_paths(
+            ctx,
+            &self.apps,
+            pattern_options(self.regex, self.case_sensitive),
+        )
+        .await?;
+
+        if self.dry_run {
+            let plan = cache_entries
+                .into_iter()
+                .map(|entry| Operation::RemoveFile {
+                    path: entry.file_path,
+                })
+                .collect::<Plan>();
+
+            return Format::resolve(self.format, self.json).render(&plan);
+        }
 
         let total_entires = cache_entries.len();
         let total_size = cache_entries
@@ -33,7 +69,7 @@ impl Command for Args {
             eprintln!("Removed: {}", entry.url);
         }
 
-        eprintln_bright_yellow!("Deleted {total_entires} files, {total_size}");
+        eprintln_styled!(Highlight, "Deleted {total_entires} files, {total_size}");
 
         Ok(())
     }
@@ -3,21 +3,29 @@ use clap::Parser;
 use itertools::Itertools;
 use sprinkles::contexts::ScoopContext;
 
-use crate::diagnostics::{Diagnostics, LongPathsStatus};
+use crate::{
+    diagnostics::{Diagnostics, LongPathsStatus},
+    output::format::Format,
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Check for common issues
 pub struct Args {
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let diagnostics = Diagnostics::collect(ctx)?;
 
-        if self.json {
-            println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
+            format.print_value(&diagnostics)?;
             return Ok(());
         }
 
@@ -24,6 +24,8 @@ use ratatui::{
 use serde::Serialize;
 use sprinkles::contexts::ScoopContext;
 
+use crate::output::format::Format;
+
 mod contributors {
     include!(concat!(env!("OUT_DIR"), "/contributors.rs"));
 
@@ -119,11 +121,16 @@ pub struct Args {
 
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
     async fn runner(self, _: &impl ScoopContext) -> anyhow::Result<()> {
-        if self.json {
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
             #[derive(Debug, Clone, Serialize)]
             struct JsonOutput<'a> {
                 contributors: Vec<Contributor<'a>>,
@@ -170,9 +177,7 @@ impl super::Command for Args {
                 packages,
             };
 
-            let output = serde_json::to_string_pretty(&output)?;
-
-            println!("{output}");
+            format.print_value(&output)?;
         } else if console::colors_enabled() {
             self.terminal_ui()?;
         } else {
@@ -1,12 +1,18 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use itertools::Itertools;
 use sprinkles::{
     contexts::ScoopContext,
     packages::reference::{manifest, package},
 };
 
+use super::{rdepends, Command};
 use crate::{
-    abandon,
-    output::sectioned::{Children, Section, Sections},
+    errors::Error,
+    models::depends::Graph,
+    output::{
+        format::Format,
+        sectioned::{Children, Section, Sections, Text},
+    },
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -18,26 +24,103 @@ pub struct Args {
     #[clap(help = "The bucket of the given package")]
     bucket: Option<String>,
 
-    // TODO: Implement recursion?
-    // recursive: bool,
+    #[clap(
+        short,
+        long,
+        help = "Resolve the full dependency graph, and list it in install order"
+    )]
+    recursive: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "recursive",
+        help = "List the packages which depend on the given package instead"
+    )]
+    reverse: bool,
+
+    #[clap(
+        short = 'A',
+        long,
+        requires = "reverse",
+        help = "When listing reverse dependencies, also search every manifest in every bucket"
+    )]
+    all_buckets: bool,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = GraphFormat::Text,
+        conflicts_with = "reverse",
+        help = "The format to draw the dependency graph in. `dot` and `mermaid` always resolve the full graph, and take precedence over `--format`"
+    )]
+    graph: GraphFormat,
+
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    Text,
+    Dot,
+    Mermaid,
 }
 
-impl super::Command for Args {
+impl Command for Args {
     async fn runner(mut self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         if let Some(bucket) = self.bucket {
             self.package.set_bucket(bucket)?;
         }
 
+        if self.reverse {
+            return rdepends::Args::new(self.package, self.all_buckets, self.json, self.format)
+                .runner(ctx)
+                .await;
+        }
+
+        let format = Format::resolve(self.format, self.json);
+
         let manifests = self.package.list_manifests(ctx).await?;
 
         if manifests.is_empty() {
-            abandon!("Could not find any packages matching: {}", self.package);
+            return Err(Error::not_found(format!(
+                "Could not find any packages matching: {}",
+                self.package
+            ))
+            .into());
         };
 
-        if self.json {
-            println!("{}", serde_json::to_string(&manifests)?);
+        if self.recursive || self.graph != GraphFormat::Text {
+            let graphs = manifests
+                .into_iter()
+                .map(|manifest| Graph::resolve(ctx, manifest))
+                .collect_vec();
+
+            if self.graph != GraphFormat::Text {
+                let rendered = graphs.iter().map(|graph| match self.graph {
+                    GraphFormat::Dot => graph.to_dot(),
+                    GraphFormat::Mermaid => graph.to_mermaid(),
+                    GraphFormat::Text => unreachable!(),
+                });
+
+                println!("{}", rendered.format("\n\n"));
+            } else if !format.is_table() {
+                format.print_value(&graphs)?;
+            } else {
+                let output: Sections<Text<String>> =
+                    graphs.iter().flat_map(Self::graph_sections).collect();
+
+                println!("{output}");
+            }
+
+            return Ok(());
+        }
+
+        if !format.is_table() {
+            format.print_value(&manifests)?;
             return Ok(());
         }
 
@@ -62,4 +145,61 @@ impl super::Command for Args {
     }
 }
 
+impl Args {
+    fn graph_sections(graph: &Graph) -> Vec<Section<Text<String>>> {
+        let root = graph.order.last().map_or(graph.root.clone(), |node| {
+            format!("{}/{}", node.bucket, node.name)
+        });
+
+        let order = graph
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                Text::new(format!(
+                    "{}. {}/{} ({}){}",
+                    i + 1,
+                    node.bucket,
+                    node.name,
+                    node.version,
+                    if node.installed { " [installed]" } else { "" }
+                ))
+            })
+            .collect_vec();
+
+        let mut sections =
+            vec![Section::new(Children::from(order))
+                .with_title(format!("Install order for '{root}':"))];
+
+        if !graph.unresolved.is_empty() {
+            let unresolved = graph
+                .unresolved
+                .iter()
+                .cloned()
+                .map(Text::new)
+                .collect_vec();
+
+            sections.push(
+                Section::new(Children::from(unresolved))
+                    .with_title(format!("Unresolved dependencies for '{root}':")),
+            );
+        }
+
+        if !graph.cycles.is_empty() {
+            let cycles = graph
+                .cycles
+                .iter()
+                .map(|cycle| Text::new(cycle.join(" -> ")))
+                .collect_vec();
+
+            sections.push(
+                Section::new(Children::from(cycles))
+                    .with_title(format!("Dependency cycles for '{root}':")),
+            );
+        }
+
+        sections
+    }
+}
+
 // note to self, use `phpstudy-lagecy-scoop` to test this command
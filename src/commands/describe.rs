@@ -1,10 +1,15 @@
 use clap::Parser;
 
-use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::Manifest};
+use serde::Serialize;
+use sprinkles::{buckets::Bucket, contexts::ScoopContext};
 
 use crate::{
     commands::{DeprecationMessage, DeprecationWarning},
-    output::sectioned::{Children, Section, Sections, Text},
+    models::index::{BucketIndex, Entry},
+    output::{
+        format::Format,
+        sectioned::{Children, Section, Sections, Text},
+    },
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -15,6 +20,36 @@ pub struct Args {
 
     #[clap(short, long, help = "The bucket to exclusively search in")]
     bucket: Option<String>,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// The description of a package in a bucket
+struct Description {
+    name: String,
+    bucket: String,
+    version: String,
+    description: Option<String>,
+    homepage: Option<String>,
+    license: Option<String>,
+}
+
+impl From<&Entry> for Description {
+    fn from(entry: &Entry) -> Self {
+        Self {
+            name: entry.name.clone(),
+            bucket: entry.bucket.clone(),
+            version: entry.version.clone(),
+            description: entry.description.clone(),
+            homepage: entry.homepage.clone(),
+            license: entry.license.clone(),
+        }
+    }
 }
 
 impl super::Command for Args {
@@ -26,31 +61,49 @@ impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let buckets = Bucket::one_or_all(ctx, self.bucket)?;
 
-        let manifests: Vec<(String, String, Manifest)> = buckets
+        let entries: Vec<Entry> = buckets
             .iter()
-            .filter_map(|bucket| match bucket.get_manifest(&self.package) {
-                Ok(manifest) => Some((self.package.clone(), bucket.name().to_string(), manifest)),
-                Err(_) => None,
+            .filter_map(|bucket| {
+                if let Some(index) = BucketIndex::fresh(ctx, bucket) {
+                    return index
+                        .entries
+                        .into_iter()
+                        .find(|entry| entry.name == self.package);
+                }
+
+                match bucket.get_manifest(&self.package) {
+                    Ok(manifest) => Some(Entry::new(&self.package, bucket.name(), &manifest)),
+                    Err(_) => None,
+                }
             })
             .collect();
 
-        let sectioned = manifests
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
+            let descriptions = entries.iter().map(Description::from).collect::<Vec<_>>();
+
+            format.print_rows(&descriptions, None)?;
+            return Ok(());
+        }
+
+        let sectioned = entries
             .iter()
-            .map(|(package, bucket, manifest)| {
-                let title = format!("{package} in \"{bucket}\":");
+            .map(|entry| {
+                let title = format!("{} in \"{}\":", entry.name, entry.bucket);
 
                 let mut description: Vec<Text<String>> = vec![];
 
-                if let Some(ref pkg_description) = manifest.description {
+                if let Some(ref pkg_description) = entry.description {
                     description.push(pkg_description.clone().into());
                 }
 
-                description.push(format!("Version: {}", manifest.version).into());
+                description.push(format!("Version: {}", entry.version).into());
 
-                if let Some(ref homepage) = manifest.homepage {
+                if let Some(ref homepage) = entry.homepage {
                     description.push(format!("Homepage: {homepage}").into());
                 }
-                if let Some(ref license) = manifest.license {
+                if let Some(ref license) = entry.license {
                     description.push(format!("License: {license}").into());
                 }
 
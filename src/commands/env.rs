@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use sfsu_macros::Runnable;
+use sprinkles::{config, contexts::ScoopContext};
+
+mod apply;
+mod check;
+
+use crate::{
+    commands::CommandRunner,
+    errors::Error,
+    models::scoopfile::{Scoopfile, DEFAULT_PATHS},
+};
+
+#[derive(Debug, Clone, Subcommand, Runnable)]
+enum Commands {
+    Check(check::Args),
+    Apply(apply::Args),
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Check or apply a Scoopfile, which declares the buckets and apps a project needs
+pub struct Args {
+    #[command(subcommand)]
+    command: Commands,
+
+    #[clap(
+        global = true,
+        help = "The Scoopfile to use. Defaults to Scoopfile.toml or Scoopfile.json in the current directory"
+    )]
+    path: Option<PathBuf>,
+}
+
+impl super::Command for Args {
+    #[inline]
+    async fn runner(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> Result<(), anyhow::Error> {
+        self.command.run(ctx).await
+    }
+}
+
+/// Read the provided Scoopfile, or find one in the current directory
+fn read(path: Option<PathBuf>) -> anyhow::Result<Scoopfile> {
+    let Some(path) = path.or_else(Scoopfile::find) else {
+        return Err(Error::not_found(format!(
+            "No Scoopfile found. Create {} or pass the path to one",
+            DEFAULT_PATHS.join(" or ")
+        ))
+        .into());
+    };
+
+    Scoopfile::read(&path)
+}
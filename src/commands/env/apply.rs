@@ -0,0 +1,329 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+use sprinkles::{config, contexts::ScoopContext, packages::models::install};
+
+use crate::{
+    commands::{bucket::add, Command},
+    models::{
+        index::BucketIndex,
+        lock::Item,
+        plan::{Operation, Plan},
+        scoopfile::{AppSpec, Unmet},
+    },
+    output::{
+        format::{Format, Render},
+        structured::Structured,
+        WHITESPACE,
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Add the Scoopfile's missing buckets, and list the Scoop commands to install and configure its apps
+pub struct Args {
+    #[clap(from_global)]
+    path: Option<PathBuf>,
+
+    #[clap(from_global)]
+    disable_git: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// The result of applying a Scoopfile
+pub struct Summary {
+    /// The operations which were performed, or would be with `--dry-run`
+    operations: Plan,
+    /// The Scoop commands to run to meet the app requirements
+    commands: Vec<String>,
+    /// The requirements which can't be met automatically
+    unresolved: Vec<Unmet>,
+
+    #[serde(skip)]
+    dry_run: bool,
+}
+
+impl Render for Summary {
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()> {
+        if self.operations.operations().is_empty()
+            && self.commands.is_empty()
+            && self.unresolved.is_empty()
+        {
+            writeln!(f, "All requirements are met.")?;
+            return Ok(());
+        }
+
+        if !self.operations.operations().is_empty() {
+            writeln!(
+                f,
+                "{}",
+                if self.dry_run {
+                    "Dry run: the following operations would be performed:"
+                } else {
+                    "Performed the following operations:"
+                }
+            )?;
+
+            for operation in self.operations.operations() {
+                writeln!(f, "{WHITESPACE}{operation}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        if !self.commands.is_empty() {
+            writeln!(f, "Run the following to install and configure the apps:")?;
+
+            for command in &self.commands {
+                writeln!(f, "{WHITESPACE}{command}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        if !self.unresolved.is_empty() {
+            writeln!(f, "The following requirements can't be met automatically:")?;
+
+            let table = Structured::new(&self.unresolved).with_global_options()?;
+
+            write!(f, "{table}")?;
+        }
+
+        Ok(())
+    }
+
+    fn rows(&self) -> anyhow::Result<Value> {
+        // Row based formats can only represent the commands to run
+        Ok(serde_json::to_value(&self.commands)?)
+    }
+}
+
+impl Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        let scoopfile = super::read(self.path)?;
+        let unmet = scoopfile.check(ctx)?;
+
+        let mut operations = Plan::new();
+        let mut missing_buckets = vec![];
+        let mut unresolved = vec![];
+
+        for unmet in unmet.iter().filter(|unmet| unmet.item == Item::Bucket) {
+            let spec = scoopfile
+                .buckets
+                .iter()
+                .find(|spec| spec.name == unmet.name);
+
+            match spec {
+                Some(spec) if unmet.is_missing() => {
+                    // Unknown buckets without a url can't be added, so leave them for the user
+                    let Some(url) = spec.url(ctx) else {
+                        unresolved.push(unmet.clone());
+                        continue;
+                    };
+
+                    operations.push(Operation::GitClone {
+                        url: url.clone(),
+                        path: ctx.buckets_path().join(&spec.name),
+                        branch: spec.branch.clone(),
+                    });
+                    operations.push(Operation::WriteFile {
+                        path: BucketIndex::path(ctx, &spec.name),
+                    });
+                    missing_buckets.push((spec, url));
+                }
+                // Changing a bucket's source or branch could break the apps installed from it
+                _ => unresolved.push(unmet.clone()),
+            }
+        }
+
+        // Scoop won't update held apps, so they must be unheld first
+        let held = install::Manifest::list_all_unchecked(ctx)?
+            .iter()
+            .filter(|app| app.hold.unwrap_or_default())
+            .map(|app| unsafe { app.name() }.to_lowercase())
+            .collect::<HashSet<_>>();
+
+        let mut commands = vec![];
+        for spec in &scoopfile.apps {
+            let (_, name) = spec.parts();
+            let unmet = unmet
+                .iter()
+                .filter(|unmet| unmet.item == Item::App && unmet.name == name)
+                .collect::<Vec<_>>();
+
+            match app_commands(spec, &unmet, held.contains(&name.to_lowercase())) {
+                Some(app_commands) => commands.extend(app_commands),
+                None => unresolved.extend(unmet.into_iter().cloned()),
+            }
+        }
+
+        if !self.dry_run {
+            for (spec, url) in missing_buckets {
+                eprintln!("Adding bucket {}", spec.name);
+
+                add::clone(
+                    ctx,
+                    &spec.name,
+                    &url,
+                    spec.branch.as_deref(),
+                    self.disable_git,
+                )
+                .await?;
+            }
+        }
+
+        let summary = Summary {
+            operations,
+            commands,
+            unresolved,
+            dry_run: self.dry_run,
+        };
+
+        Format::resolve(self.format, self.json).render(&summary)
+    }
+}
+
+/// The Scoop commands which meet an app's unmet requirements
+///
+/// Returns `None` if Scoop can't meet them, i.e the installed version is newer than the constraint allows
+fn app_commands(spec: &AppSpec, unmet: &[&Unmet], held: bool) -> Option<Vec<String>> {
+    if unmet.is_empty() {
+        return Some(vec![]);
+    }
+
+    let (_, name) = spec.parts();
+    let unmet_requirement =
+        |requirement: &str| unmet.iter().any(|unmet| unmet.requirement == requirement);
+
+    let exact_version = spec.version.as_ref().and_then(|version| version.exact());
+
+    let mut commands = vec![];
+    let mut unheld = false;
+
+    let missing = unmet_requirement("installed");
+    // Scoop can only change the bucket, architecture or exact version of an app by reinstalling it
+    let reinstall = unmet_requirement("bucket")
+        || unmet_requirement("arch")
+        || (unmet_requirement("version") && exact_version.is_some());
+
+    if missing || reinstall {
+        if reinstall {
+            commands.push(format!("scoop uninstall {name}"));
+        }
+
+        let mut install = vec!["scoop install".to_string()];
+        if let Some(ref arch) = spec.arch {
+            install.push(format!("--arch {arch}"));
+        }
+        install.push(match exact_version {
+            Some(version) => format!("{}@{version}", spec.name),
+            None => spec.name.clone(),
+        });
+
+        commands.push(install.join(" "));
+    } else if let Some(version) = unmet.iter().find(|unmet| unmet.requirement == "version") {
+        // Updating only moves further from a constraint the installed version is already above
+        if let (Some(constraint), Some(actual)) = (&spec.version, &version.actual) {
+            if !constraint.allows_newer(actual) {
+                return None;
+            }
+        }
+
+        if held {
+            commands.push(format!("scoop unhold {name}"));
+            unheld = true;
+        }
+        commands.push(format!("scoop update {name}"));
+    }
+
+    match spec.held {
+        Some(true) if missing || reinstall || unheld || unmet_requirement("held") => {
+            commands.push(format!("scoop hold {name}"));
+        }
+        Some(false) if unmet_requirement("held") && !unheld => {
+            commands.push(format!("scoop unhold {name}"));
+        }
+        // Restore the hold if the app was only unheld to update it
+        None if unheld => commands.push(format!("scoop hold {name}")),
+        _ => {}
+    }
+
+    Some(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        let spec: AppSpec = serde_json::from_value(serde_json::json!({
+            "name": "extras/sfsu",
+            "version": "1.14.0",
+            "arch": "64bit",
+            "held": true,
+        }))
+        .unwrap();
+
+        let unmet = |requirement| Unmet {
+            item: Item::App,
+            name: "sfsu".into(),
+            requirement,
+            expected: String::new(),
+            actual: None,
+        };
+
+        assert_eq!(
+            app_commands(&spec, &[&unmet("installed")], false).unwrap(),
+            [
+                "scoop install --arch 64bit extras/sfsu@1.14.0",
+                "scoop hold sfsu"
+            ]
+        );
+        assert_eq!(
+            app_commands(&spec, &[&unmet("version")], false).unwrap(),
+            [
+                "scoop uninstall sfsu",
+                "scoop install --arch 64bit extras/sfsu@1.14.0",
+                "scoop hold sfsu"
+            ]
+        );
+        assert_eq!(
+            app_commands(&spec, &[&unmet("held")], false).unwrap(),
+            ["scoop hold sfsu"]
+        );
+        assert!(app_commands(&spec, &[], false).unwrap().is_empty());
+
+        let spec: AppSpec = serde_json::from_value(serde_json::json!({
+            "name": "extras/sfsu",
+            "version": ">=1.14, <2",
+        }))
+        .unwrap();
+
+        let version = |actual: &str| Unmet {
+            actual: Some(actual.into()),
+            ..unmet("version")
+        };
+
+        assert_eq!(
+            app_commands(&spec, &[&version("1.13.0")], false).unwrap(),
+            ["scoop update sfsu"]
+        );
+        assert_eq!(
+            app_commands(&spec, &[&version("1.13.0")], true).unwrap(),
+            ["scoop unhold sfsu", "scoop update sfsu", "scoop hold sfsu"]
+        );
+        // Updating can't bring the version back under the upper bound
+        assert_eq!(app_commands(&spec, &[&version("2.1.0")], false), None);
+    }
+}
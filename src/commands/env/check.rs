@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use serde::Serialize;
+use sprinkles::{config, contexts::ScoopContext};
+
+use crate::{
+    commands::Query,
+    errors::{Error, Kind},
+    models::scoopfile::Unmet,
+    output::{
+        format::{Format, Render},
+        structured::Structured,
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Report the requirements of the Scoopfile which are not met
+pub struct Args {
+    #[clap(from_global)]
+    path: Option<PathBuf>,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+/// The requirements which are not met
+pub struct Output(pub Vec<Unmet>);
+
+impl Render for Output {
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            writeln!(f, "All requirements are met.")?;
+        } else {
+            let table = Structured::new(&self.0).with_global_options()?;
+
+            write!(f, "{table}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Query for Args {
+    type Output = Output;
+
+    fn format(&self) -> Format {
+        Format::resolve(self.format, self.json)
+    }
+
+    async fn query(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Output> {
+        let scoopfile = super::read(self.path)?;
+
+        Ok(Output(scoopfile.check(ctx)?))
+    }
+
+    fn check(output: &Output) -> anyhow::Result<()> {
+        if output.0.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::new(
+            Kind::Drift,
+            format!("{} requirements are not met", output.0.len()),
+        )
+        .into())
+    }
+}
@@ -1,18 +1,110 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use sprinkles::{config, contexts::ScoopContext};
 
-use crate::models::export::Export;
+use crate::{
+    models::export::{Diff, Export, Filter},
+    output::format::Format,
+    patterns::{self, Pattern},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Exports installed apps, buckets (and optionally configs) in JSON format
 pub struct Args {
     #[clap(short, long, help = "Export the scoop config as well")]
     config: bool,
+
+    #[clap(
+        long,
+        value_name = "PATTERN",
+        value_delimiter = ',',
+        help = "Only export apps matching these glob patterns"
+    )]
+    apps: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "PATTERN",
+        value_delimiter = ',',
+        help = "Only export buckets matching these glob patterns"
+    )]
+    buckets: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Treat the app and bucket patterns as regexes, using Rust Regex syntax"
+    )]
+    regex: bool,
+
+    #[clap(
+        long,
+        help = "Whether or not the app and bucket patterns should match case-sensitively"
+    )]
+    case_sensitive: bool,
+
+    #[clap(long, help = "Exclude held apps")]
+    exclude_held: bool,
+
+    #[clap(long, help = "Exclude apps whose install failed")]
+    exclude_failed: bool,
+
+    #[clap(
+        long,
+        value_name = "KEY",
+        value_delimiter = ',',
+        requires = "config",
+        help = "Redact these config keys, as well as the sensitive keys redacted by default"
+    )]
+    redact: Vec<String>,
+
+    #[clap(
+        long,
+        requires = "config",
+        help = "Don't redact sensitive config keys (api keys, tokens and proxy credentials)"
+    )]
+    no_redact: bool,
+
+    #[clap(
+        long,
+        value_name = "EXPORT",
+        help = "Compare against a reference export file, listing missing, extra and changed apps, buckets and config keys"
+    )]
+    diff: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "EXPORT",
+        requires = "diff",
+        help = "Compare this export file against the reference, rather than this machine"
+    )]
+    against: Option<PathBuf>,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        if let Some(ref reference) = self.diff {
+            let reference = Export::read(reference)?;
+
+            let actual = match self.against {
+                Some(ref against) => Export::read(against)?,
+                None => Export::load(ctx)?,
+            };
+
+            let diff = Diff::new(&reference, &actual)?;
+
+            return Format::resolve(self.format, self.json).render(&diff);
+        }
+
+        let filter = self.filter()?;
+
         let export = {
             let mut export = Export::load(ctx)?;
 
@@ -20,13 +112,39 @@ impl super::Command for Args {
                 export.config = None;
             }
 
+            export.filter(&filter);
+
             export
         };
 
-        let output = serde_json::to_string_pretty(&export)?;
+        let output = serde_json::to_string_pretty(
+            &export.to_redacted_value(&self.redact, !self.no_redact)?,
+        )?;
 
         println!("{output}");
 
         Ok(())
     }
 }
+
+impl Args {
+    fn filter(&self) -> anyhow::Result<Filter> {
+        let options = patterns::Options::new()
+            .regex(self.regex)
+            .case_sensitive(self.case_sensitive);
+
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::new(pattern, options))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Filter {
+            apps: compile(&self.apps)?,
+            buckets: compile(&self.buckets)?,
+            exclude_held: self.exclude_held,
+            exclude_failed: self.exclude_failed,
+        })
+    }
+}
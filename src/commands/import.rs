@@ -0,0 +1,307 @@
+use std::{fmt::Write, path::PathBuf};
+
+use clap::Parser;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use sprinkles::{config, contexts::ScoopContext};
+
+use crate::{
+    models::{
+        export::{is_redacted, redact_sensitive, App, Bucket, Export, SKIPPED_CONFIG_KEYS},
+        index::BucketIndex,
+        plan::{Operation, Plan},
+    },
+    output::{
+        format::{Format, Render},
+        WHITESPACE,
+    },
+};
+
+use super::bucket::add;
+
+#[derive(Debug, Clone, Parser)]
+/// Restore buckets (and optionally configs) from an `sfsu export` or `scoop export` file, and list the apps left to install
+pub struct Args {
+    #[clap(help = "The export file to import")]
+    path: PathBuf,
+
+    #[clap(short, long, help = "Restore the scoop config as well")]
+    config: bool,
+
+    #[clap(from_global)]
+    disable_git: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+/// The buckets and apps from the export which are already installed
+pub struct Satisfied {
+    buckets: Vec<String>,
+    apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// An app from the export which is not installed
+pub struct Install {
+    /// The package reference to install, i.e `extras/sfsu`
+    reference: String,
+    /// The version installed on the exporting machine
+    version: String,
+}
+
+impl From<&App> for Install {
+    fn from(app: &App) -> Self {
+        // Apps installed from a url or path keep it as their source
+        let reference = if app.source.contains(['/', '\\']) {
+            app.source.clone()
+        } else {
+            format!("{}/{}", app.source, app.name)
+        };
+
+        Self {
+            reference,
+            version: app.version.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// The result of an import
+pub struct Summary {
+    /// The buckets and apps which are already installed
+    satisfied: Satisfied,
+    /// The operations which were performed, or would be with `--dry-run`
+    operations: Plan,
+    /// The apps which still need to be installed with Scoop
+    install: Vec<Install>,
+
+    #[serde(skip)]
+    dry_run: bool,
+}
+
+impl Render for Summary {
+    fn render_table(&self, f: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(
+            f,
+            "Already satisfied: {} buckets, {} apps",
+            self.satisfied.buckets.len(),
+            self.satisfied.apps.len()
+        )?;
+
+        if !self.operations.operations().is_empty() {
+            writeln!(
+                f,
+                "\n{}",
+                if self.dry_run {
+                    "Dry run: the following operations would be performed:"
+                } else {
+                    "Performed the following operations:"
+                }
+            )?;
+
+            for operation in self.operations.operations() {
+                writeln!(f, "{WHITESPACE}{operation}")?;
+            }
+        }
+
+        if self.install.is_empty() {
+            writeln!(f, "\nAll apps are installed.")?;
+        } else {
+            writeln!(
+                f,
+                "\nInstall the remaining {} apps with:\n{WHITESPACE}scoop install {}",
+                self.install.len(),
+                self.install
+                    .iter()
+                    .map(|install| &install.reference)
+                    .join(" ")
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn rows(&self) -> anyhow::Result<Value> {
+        // Row based formats can only represent the apps left to install
+        Ok(serde_json::to_value(&self.install)?)
+    }
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        let export = Export::read(&self.path)?;
+
+        let mut satisfied = Satisfied::default();
+        let mut operations = Plan::new();
+
+        let mut missing_buckets = vec![];
+        for bucket in &export.buckets {
+            if ctx.buckets_path().join(&bucket.name).exists() {
+                satisfied.buckets.push(bucket.name.clone());
+            } else {
+                operations.push(Operation::GitClone {
+                    url: bucket.source.clone(),
+                    path: ctx.buckets_path().join(&bucket.name),
+                    branch: None,
+                });
+                operations.push(Operation::WriteFile {
+                    path: BucketIndex::path(ctx, &bucket.name),
+                });
+                missing_buckets.push(bucket);
+            }
+        }
+
+        let config = match export.config {
+            Some(ref imported) if self.config => Some(merge_config(ctx.config(), imported)?),
+            _ => None,
+        };
+
+        if let Some((_, ref changes)) = config {
+            for (key, value) in changes {
+                // Only the printed operation is redacted, the saved config keeps the real values
+                operations.push(Operation::SetConfig {
+                    key: key.clone(),
+                    value: match redact_sensitive(key, value) {
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    },
+                });
+            }
+        }
+
+        let mut install = vec![];
+        for app in &export.apps {
+            if ctx.app_installed(&app.name)? {
+                satisfied.apps.push(app.name.clone());
+            } else {
+                install.push(Install::from(app));
+            }
+        }
+
+        if !self.dry_run {
+            for bucket in missing_buckets {
+                add_bucket(ctx, bucket, self.disable_git).await?;
+            }
+
+            if let Some((config, changes)) = config {
+                if !changes.is_empty() {
+                    config.save()?;
+                }
+            }
+        }
+
+        let summary = Summary {
+            satisfied,
+            operations,
+            install,
+            dry_run: self.dry_run,
+        };
+
+        Format::resolve(self.format, self.json).render(&summary)
+    }
+}
+
+async fn add_bucket(
+    ctx: &impl ScoopContext,
+    bucket: &Bucket,
+    disable_git: bool,
+) -> anyhow::Result<()> {
+    eprintln!("Adding bucket {}", bucket.name);
+
+    add::clone(ctx, &bucket.name, &bucket.source, None, disable_git).await
+}
+
+/// Apply the imported config on top of the current config
+///
+/// Returns the merged config, and the keys which changed
+fn merge_config(
+    current: &config::Scoop,
+    imported: &config::Scoop,
+) -> anyhow::Result<(config::Scoop, Vec<(String, Value)>)> {
+    let Value::Object(mut merged) = serde_json::to_value(current)? else {
+        anyhow::bail!("Scoop config is not an object");
+    };
+    let Value::Object(imported) = serde_json::to_value(imported)? else {
+        anyhow::bail!("Imported config is not an object");
+    };
+
+    let mut changes = vec![];
+
+    for (key, value) in imported {
+        if value.is_null() || is_redacted(&value) || SKIPPED_CONFIG_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+
+        if merged.get(&key) != Some(&value) {
+            changes.push((key.clone(), value.clone()));
+            merged.insert(key, value);
+        }
+    }
+
+    Ok((serde_json::from_value(Value::Object(merged))?, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_reference() {
+        let app = |source: &str| App {
+            name: "sfsu".into(),
+            source: source.into(),
+            updated: String::new(),
+            version: "1.0.0".into(),
+            info: String::new(),
+        };
+
+        assert_eq!(Install::from(&app("extras")).reference, "extras/sfsu");
+        assert_eq!(
+            Install::from(&app("https://example.com/sfsu.json")).reference,
+            "https://example.com/sfsu.json"
+        );
+    }
+
+    #[test]
+    fn test_merge_redacted_config() {
+        let config = |value| serde_json::from_value::<config::Scoop>(value).unwrap();
+
+        let export = Export {
+            config: Some(config(serde_json::json!({
+                "gh_token": "ghp_exported",
+                "proxy": "user:password@proxy.example.com:8080",
+                "private_hosts": [{ "match": "example.com", "headers": "Authorization=secret" }],
+            }))),
+            apps: vec![],
+            buckets: vec![],
+        };
+
+        let path =
+            std::env::temp_dir().join(format!("sfsu-import-test-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            serde_json::to_string(&export.to_redacted_value(&[], true).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let imported = Export::read(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let current = config(serde_json::json!({ "gh_token": "ghp_current" }));
+        let (merged, changes) = merge_config(&current, &imported.unwrap().config.unwrap()).unwrap();
+
+        assert!(changes.is_empty(), "{changes:?}");
+        assert_eq!(
+            serde_json::to_value(merged).unwrap()["gh_token"],
+            "ghp_current"
+        );
+    }
+}
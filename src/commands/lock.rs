@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use sfsu_macros::Runnable;
+use sprinkles::{config, contexts::ScoopContext};
+
+mod verify;
+mod write;
+
+use crate::{commands::CommandRunner, models::lock::Lock, output::format::Format};
+
+#[derive(Debug, Clone, Subcommand, Runnable)]
+enum Commands {
+    Write(write::Args),
+    Verify(verify::Args),
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Write or verify a lockfile, pinning each bucket's commit and each app's version, architecture and manifest
+pub struct Args {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
+    #[clap(
+        global = true,
+        help = "The lockfile to write or verify",
+        default_value_os_t = Lock::default_path()
+    )]
+    path: PathBuf,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+impl super::Command for Args {
+    async fn runner(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> Result<(), anyhow::Error> {
+        let command = self.command.unwrap_or(Commands::Write(write::Args {
+            path: self.path,
+            dry_run: self.dry_run,
+            json: self.json,
+            format: self.format,
+        }));
+
+        command.run(ctx).await
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use serde::Serialize;
+use sprinkles::{config, contexts::ScoopContext};
+
+use crate::{
+    commands::Query,
+    errors::{Error, Kind},
+    models::lock::{Drift, Lock},
+    output::{
+        format::{Format, Render},
+        structured::Structured,
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Compare the current state against the lockfile, and report any drift
+pub struct Args {
+    #[clap(from_global)]
+    path: PathBuf,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+/// The differences between the lockfile and the current state
+pub struct Output(pub Vec<Drift>);
+
+impl Render for Output {
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            writeln!(f, "Everything matches the lockfile.")?;
+        } else {
+            let table = Structured::new(&self.0).with_global_options()?;
+
+            write!(f, "{table}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Query for Args {
+    type Output = Output;
+
+    fn format(&self) -> Format {
+        Format::resolve(self.format, self.json)
+    }
+
+    async fn query(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Output> {
+        let lock = Lock::read(&self.path)?;
+        let actual = Lock::load(ctx)?;
+
+        Ok(Output(lock.drift(&actual)))
+    }
+
+    fn check(output: &Output) -> anyhow::Result<()> {
+        if output.0.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::new(
+            Kind::Drift,
+            format!("Found {} differences from the lockfile", output.0.len()),
+        )
+        .into())
+    }
+}
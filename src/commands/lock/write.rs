@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    commands::Command,
+    models::{
+        lock::Lock,
+        plan::{Operation, Plan},
+    },
+    output::format::Format,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Write the current state to the lockfile
+pub struct Args {
+    #[clap(from_global)]
+    pub(super) path: PathBuf,
+
+    #[clap(from_global)]
+    pub(super) dry_run: bool,
+
+    #[clap(from_global)]
+    pub(super) json: bool,
+
+    #[clap(from_global)]
+    pub(super) format: Option<Format>,
+}
+
+impl Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
+        let lock = Lock::load(ctx)?;
+
+        if self.dry_run {
+            let plan = Plan::from_iter([Operation::WriteFile { path: self.path }]);
+
+            return Format::resolve(self.format, self.json).render(&plan);
+        }
+
+        lock.write(&self.path)?;
+
+        eprintln!(
+            "Locked {} buckets and {} apps to {}",
+            lock.buckets.len(),
+            lock.apps.len(),
+            self.path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,20 @@
+use std::fmt::Write;
+
 use clap::{Parser, Subcommand};
-use serde_json::Map;
-use sfsu_macros::Runnable;
+use serde::Serialize;
+use serde_json::Value;
 use sprinkles::{config, contexts::ScoopContext};
 
-use super::{Command, CommandRunner, DeprecationMessage, DeprecationWarning};
+use super::{DeprecationMessage, DeprecationWarning, Query};
+use crate::{
+    errors::Error,
+    output::format::{Format, Render},
+};
 
 pub mod apps;
 pub mod buckets;
 
-#[derive(Debug, Clone, Subcommand, Runnable)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum Commands {
     Apps(apps::Args),
     Buckets(buckets::Args),
@@ -20,49 +26,101 @@ pub struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
+    #[clap(
+        short = 'A',
+        long,
+        help = "Check every bucket for the highest available version of each app"
+    )]
+    all_buckets: bool,
+
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+/// The outdated apps and/or buckets
+pub enum Output {
+    Apps(apps::Output),
+    Buckets(buckets::Output),
+    All {
+        outdated_apps: apps::Output,
+        outdated_buckets: buckets::Output,
+    },
+}
+
+impl Render for Output {
+    fn render_table(&self, f: &mut dyn Write) -> anyhow::Result<()> {
+        match self {
+            Output::Apps(apps) => apps.render_table(f),
+            Output::Buckets(buckets) => buckets.render_table(f),
+            Output::All {
+                outdated_apps,
+                outdated_buckets,
+            } => {
+                writeln!(f, "Outdated Apps:")?;
+                outdated_apps.render_table(f)?;
+                writeln!(f, "\nOutdated Buckets:")?;
+                outdated_buckets.render_table(f)
+            }
+        }
+    }
+
+    fn rows(&self) -> anyhow::Result<Value> {
+        match self {
+            // Row based formats can only represent one table
+            Output::All { .. } => Err(Error::invalid_input(
+                "Outdated apps and buckets can't be printed as a single table. Use `outdated apps` or `outdated buckets`, or a JSON or YAML format",
+            )
+            .into()),
+            output => Ok(serde_json::to_value(output)?),
+        }
+    }
 }
 
-impl Command for Args {
+impl Query for Args {
     const DEPRECATED: Option<DeprecationWarning> = Some(DeprecationWarning {
         message: DeprecationMessage::Replacement("sfsu status"),
         version: Some(2.0),
     });
 
-    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
-        if let Some(command) = self.command {
-            command.run(ctx).await
-        } else {
-            if self.json {
-                let mut map = Map::new();
-
-                let apps = apps::Args { json: self.json }
-                    .run_direct(ctx, false)?
-                    .unwrap_or_default();
-
-                let buckets = buckets::Args { json: self.json }
-                    .run_direct(ctx, false)?
-                    .unwrap_or_default();
-
-                map.insert("outdated_apps".into(), apps.into());
-                map.insert("outdated_buckets".into(), buckets.into());
-
-                let output = serde_json::to_string_pretty(&map)?;
-
-                println!("{output}");
-            } else {
-                println!("Outdated Apps:");
-                Commands::Apps(apps::Args { json: self.json })
-                    .run(ctx)
-                    .await?;
-                println!("\nOutdated Buckets:");
-                Commands::Buckets(buckets::Args { json: self.json })
-                    .run(ctx)
-                    .await?;
-            }
+    type Output = Output;
 
-            Ok(())
+    fn format(&self) -> Format {
+        Format::resolve(self.format, self.json)
+    }
+
+    async fn query(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Output> {
+        match self.command {
+            Some(Commands::Apps(apps)) => Ok(Output::Apps(apps.query(ctx).await?)),
+            Some(Commands::Buckets(buckets)) => Ok(Output::Buckets(buckets.query(ctx).await?)),
+            None => {
+                let outdated_apps = apps::Args {
+                    json: self.json,
+                    format: self.format,
+                    all_buckets: self.all_buckets,
+                }
+                .query(ctx)
+                .await?;
+
+                let outdated_buckets = buckets::Args {
+                    json: self.json,
+                    format: self.format,
+                }
+                .query(ctx)
+                .await?;
+
+                Ok(Output::All {
+                    outdated_apps,
+                    outdated_buckets,
+                })
+            }
         }
     }
 }
@@ -1,39 +1,55 @@
+use std::fmt::Write;
+
 use clap::Parser;
-use itertools::Itertools;
 use rayon::prelude::*;
-use sprinkles::{buckets::Bucket, contexts::ScoopContext};
+use serde::Serialize;
+use sprinkles::{buckets::Bucket, config, contexts::ScoopContext};
+
+use crate::{
+    commands::Query,
+    output::format::{Format, Render},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// List outdated buckets
 pub struct Args {
     #[clap(from_global)]
     pub(super) json: bool,
+
+    #[clap(from_global)]
+    pub(super) format: Option<Format>,
 }
 
-impl super::super::Command for Args {
-    async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        self.run_direct(ctx, true)?;
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+/// The names of the outdated buckets
+pub struct Output(pub Vec<String>);
+
+impl Render for Output {
+    fn render_table(&self, f: &mut dyn Write) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            writeln!(f, "All buckets are up to date!")?;
+        }
+
+        for bucket in &self.0 {
+            writeln!(f, "❌ `{bucket}` bucket is out of date")?;
+        }
 
         Ok(())
     }
 }
 
-impl Args {
-    /// Special function for these subcommands which can be run in different contexts
-    ///
-    /// Will be called with `is_subcommand` as true when called via clap subcommands,
-    /// or with `is_subcommand` as false, when called directly, from the `sfsu outdated` command
+impl Query for Args {
+    type Output = Output;
 
-    // TODO: Refactor this mess into a traits system
-    // TODO: where the is a seperate command trait for those which (can) return data
-    // TODO: and those which cant
-    // TODO: alongside seperate impls with a where bound where needed
+    fn format(&self) -> Format {
+        Format::resolve(self.format, self.json)
+    }
 
-    pub fn run_direct(
+    async fn query(
         self,
-        ctx: &impl ScoopContext,
-        is_subcommand: bool,
-    ) -> Result<Option<Vec<String>>, anyhow::Error> {
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Output> {
         let outdated_buckets = Bucket::list_all(ctx)?
             .into_par_iter()
             .filter(|bucket| match bucket.outdated() {
@@ -43,29 +59,27 @@ impl Args {
                     false
                 }
             })
-            .collect::<Vec<_>>();
-
-        if self.json {
-            let outdated_bucket_names = outdated_buckets
-                .into_iter()
-                .map(|bucket| bucket.name().to_string())
-                .collect_vec();
+            .map(|bucket| bucket.name().to_string())
+            .collect();
 
-            if !is_subcommand {
-                return Ok(Some(outdated_bucket_names));
-            }
+        Ok(Output(outdated_buckets))
+    }
+}
 
-            let output = serde_json::to_string_pretty(&outdated_bucket_names)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            println!("{output}");
-        } else if outdated_buckets.is_empty() {
-            println!("All buckets are up to date!");
-        } else {
-            for bucket in outdated_buckets {
-                println!("❌ `{}` bucket is out of date", bucket.name());
-            }
-        }
+    #[test]
+    fn test_render() {
+        let mut output = String::new();
+        Output::default().render_table(&mut output).unwrap();
+        assert_eq!(output, "All buckets are up to date!\n");
 
-        Ok(None)
+        let mut output = String::new();
+        Output(vec!["extras".into()])
+            .render_table(&mut output)
+            .unwrap();
+        assert_eq!(output, "❌ `extras` bucket is out of date\n");
     }
 }
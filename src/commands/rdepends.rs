@@ -0,0 +1,134 @@
+use clap::Parser;
+use itertools::Itertools;
+use serde::Serialize;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    errors::Error,
+    models::depends::{self, Dependent},
+    output::{
+        format::Format,
+        sectioned::{Children, Section, Sections, Text},
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct Dependents {
+    installed: Vec<Dependent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buckets: Option<Vec<Dependent>>,
+}
+
+#[derive(Debug, Clone, Parser)]
+/// List the packages which depend on a given package
+pub struct Args {
+    #[clap(help = "The package to list dependents for")]
+    package: package::Reference,
+
+    #[clap(
+        short = 'A',
+        long,
+        help = "Also search every manifest in every bucket, not just installed apps"
+    )]
+    all_buckets: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+impl Args {
+    pub(super) fn new(
+        package: package::Reference,
+        all_buckets: bool,
+        json: bool,
+        format: Option<Format>,
+    ) -> Self {
+        Self {
+            package,
+            all_buckets,
+            json,
+            format,
+        }
+    }
+
+    fn dependent_section(title: String, dependents: &[Dependent]) -> Section<Text<String>> {
+        let children = dependents
+            .iter()
+            .map(|dependent| {
+                Text::new(format!(
+                    "{}/{} ({}){}",
+                    dependent.bucket,
+                    dependent.name,
+                    dependent.version,
+                    if dependent.installed {
+                        " [installed]"
+                    } else {
+                        ""
+                    }
+                ))
+            })
+            .collect_vec();
+
+        Section::new(Children::from(children)).with_title(title)
+    }
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
+        let Some(name) = self.package.name().map(|name| name.to_string()) else {
+            return Err(Error::invalid_input(format!(
+                "Could not find a package name in: {}",
+                self.package
+            ))
+            .into());
+        };
+
+        let dependents = Dependents {
+            installed: depends::installed_dependents(ctx, &name)?,
+            buckets: if self.all_buckets {
+                Some(depends::bucket_dependents(ctx, &name)?)
+            } else {
+                None
+            },
+        };
+
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
+            format.print_value(&dependents)?;
+            return Ok(());
+        }
+
+        let mut sections = vec![];
+
+        if dependents.installed.is_empty() {
+            println!("No installed apps depend on '{name}'");
+        } else {
+            sections.push(Self::dependent_section(
+                format!("Installed apps depending on '{name}':"),
+                &dependents.installed,
+            ));
+        }
+
+        if let Some(buckets) = &dependents.buckets {
+            if buckets.is_empty() {
+                println!("No bucket manifests depend on '{name}'");
+            } else {
+                sections.push(Self::dependent_section(
+                    format!("Bucket manifests depending on '{name}':"),
+                    buckets,
+                ));
+            }
+        }
+
+        if !sections.is_empty() {
+            let output: Sections<Text<String>> = sections.into_iter().collect();
+            println!("{output}");
+        }
+
+        Ok(())
+    }
+}
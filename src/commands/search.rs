@@ -2,18 +2,20 @@ use itertools::Itertools;
 use rayon::prelude::*;
 
 use clap::Parser;
-use regex::Regex;
 
-use sprinkles::{
-    buckets::Bucket,
-    contexts::ScoopContext,
-    packages::{Manifest, MergeDefaults, SearchMode},
-    Architecture,
-};
+use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::SearchMode};
 
 use crate::{
-    calm_panic::CalmUnwrap,
-    output::sectioned::{Children, Section, Sections, Text},
+    models::{
+        index::{BucketIndex, Entry},
+        search::{Field, FieldMatch, Match, Relevance},
+    },
+    output::{
+        format::Format,
+        pager,
+        sectioned::{Children, Section, Sections},
+    },
+    patterns::{self, Pattern},
 };
 
 #[derive(Debug, Clone)]
@@ -22,6 +24,7 @@ use crate::{
 pub struct MatchCriteria {
     name: bool,
     bins: Vec<String>,
+    fields: Vec<FieldMatch>,
 }
 
 impl MatchCriteria {
@@ -30,43 +33,40 @@ impl MatchCriteria {
         Self {
             name: false,
             bins: vec![],
+            fields: vec![],
         }
     }
 
-    /// Check if the name matches
-    pub fn matches(
-        file_name: &str,
-        manifest: Option<&Manifest>,
-        mode: SearchMode,
-        pattern: &Regex,
-        arch: Architecture,
-    ) -> Self {
-        let file_name = file_name.to_string();
+    /// Check if any of the provided fields match
+    pub fn with_fields(mut self, entry: &Entry, fields: &[Field], pattern: &Pattern) -> Self {
+        self.fields = fields
+            .iter()
+            .flat_map(|&field| {
+                field
+                    .values(entry)
+                    .into_iter()
+                    .filter(|value| pattern.is_match(value))
+                    .map(move |value| FieldMatch { field, value })
+            })
+            .collect();
+
+        self
+    }
 
+    /// Check if the name or binaries match
+    pub fn matches(entry: &Entry, mode: SearchMode, pattern: &Pattern) -> Self {
         let mut output = MatchCriteria::new();
 
-        if mode.match_names() && pattern.is_match(&file_name) {
+        if mode.match_names() && pattern.is_match(&entry.name) {
             output.name = true;
         }
 
-        if let Some(manifest) = manifest {
-            let binaries = manifest
-                .architecture
-                .merge_default(manifest.install_config.clone(), arch)
-                .bin
-                .map(|b| b.to_vec())
-                .unwrap_or_default();
-
-            let binary_matches = binaries
-                .into_iter()
+        if mode.match_binaries() {
+            let binary_matches = entry
+                .bins
+                .iter()
                 .filter(|binary| pattern.is_match(binary))
-                .filter_map(|b| {
-                    if pattern.is_match(&b) {
-                        Some(b.clone())
-                    } else {
-                        None
-                    }
-                });
+                .cloned();
 
             output.bins.extend(binary_matches);
         }
@@ -82,86 +82,56 @@ impl Default for MatchCriteria {
 }
 
 pub fn parse_output(
-    manifest: &Manifest,
+    entry: &Entry,
     ctx: &impl ScoopContext,
-    bucket: impl AsRef<str>,
     installed_only: bool,
-    pattern: &Regex,
+    pattern: &Pattern,
     mode: SearchMode,
-    arch: Architecture,
-) -> Option<Section<Text<String>>> {
-    // TODO: Better display of output
-
-    // This may be a bit of a hack, but it works
+    fields: &[Field],
+) -> Option<Match> {
+    let match_output =
+        MatchCriteria::matches(entry, mode, pattern).with_fields(entry, fields, pattern);
 
-    let match_output = MatchCriteria::matches(
-        unsafe { manifest.name() },
-        if mode.match_binaries() {
-            Some(manifest)
-        } else {
-            None
-        },
-        mode,
-        pattern,
-        arch,
-    );
-
-    if !match_output.name && match_output.bins.is_empty() {
+    if !match_output.name && match_output.bins.is_empty() && match_output.fields.is_empty() {
         return None;
     }
 
-    let is_installed = manifest.is_installed(ctx, Some(bucket.as_ref()));
+    let is_installed = entry.is_installed(ctx);
     if installed_only && !is_installed {
         return None;
     }
 
-    let styled_package_name = if unsafe { manifest.name() } == pattern.to_string() {
-        console::style(unsafe { manifest.name() })
-            .bold()
-            .to_string()
+    let relevance = if match_output.name && pattern.is_exact(&entry.name) {
+        Relevance::Exact
+    } else if match_output.name && pattern.is_prefix_of(&entry.name) {
+        Relevance::Prefix
+    } else if match_output.name {
+        Relevance::Name
+    } else if !match_output.bins.is_empty() {
+        Relevance::Binary
     } else {
-        unsafe { manifest.name() }.to_string()
+        Relevance::Field
     };
 
-    let installed_text = if is_installed && !installed_only {
-        "[installed] "
-    } else {
-        ""
-    };
-
-    let title = format!(
-        "{styled_package_name} ({}) {installed_text}",
-        manifest.version
-    );
-
-    let package = if mode.match_binaries() {
-        let bins = match_output
-            .bins
-            .iter()
-            .map(|output| {
-                Text::new(format!(
-                    "{}{}",
-                    crate::output::WHITESPACE,
-                    console::style(output).bold()
-                ))
-            })
-            .collect_vec();
-
-        Section::new(Children::from(bins))
-    } else {
-        Section::new(Children::None)
-    }
-    .with_title(title);
-
-    Some(package)
+    Some(
+        Match::new(entry, is_installed, match_output.bins)
+            .with_fields(match_output.fields)
+            .with_relevance(relevance)
+            .with_show_installed(!installed_only),
+    )
 }
 
 #[derive(Debug, Clone, Parser)]
 /// Search for a package
 pub struct Args {
-    #[clap(help = "The regex pattern to search for, using Rust Regex syntax")]
+    #[clap(
+        help = "The glob pattern to search for. A pattern without wildcards matches anywhere in the name"
+    )]
     pattern: String,
 
+    #[clap(long, help = "Treat the pattern as a regex, using Rust Regex syntax")]
+    regex: bool,
+
     #[clap(
         short,
         long,
@@ -177,9 +147,59 @@ pub struct Args {
 
     #[clap(short, long, help = "Search mode to use", default_value_t)]
     mode: SearchMode,
-    // TODO: Add json option
-    // #[clap(from_global)]
-    // json: bool,
+
+    #[clap(
+        short,
+        long = "field",
+        value_enum,
+        value_delimiter = ',',
+        help = "Also search the given manifest fields. Can be passed multiple times, or as a comma separated list"
+    )]
+    fields: Vec<Field>,
+
+    #[clap(
+        short,
+        long,
+        help = "Sort results by relevance: exact name matches, then prefix matches, then binary matches, then other fields"
+    )]
+    ranked: bool,
+
+    #[clap(short, long, help = "Only show the first N results")]
+    limit: Option<usize>,
+
+    #[clap(long, help = "List results without grouping them by bucket")]
+    flat: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
+}
+
+impl Args {
+    /// Group matches into sections by bucket, keeping the order of the matches
+    fn group_by_bucket(matches: Vec<Match>) -> Sections<Match> {
+        let mut buckets: Vec<(String, Vec<Match>)> = vec![];
+
+        for search_match in matches {
+            if let Some((_, bucket)) = buckets
+                .iter_mut()
+                .find(|(bucket, _)| *bucket == search_match.bucket)
+            {
+                bucket.push(search_match);
+            } else {
+                buckets.push((search_match.bucket.clone(), vec![search_match]));
+            }
+        }
+
+        buckets
+            .into_iter()
+            .map(|(bucket, matches)| {
+                Section::new(Children::from(matches)).with_title(format!("'{bucket}' bucket:"))
+            })
+            .collect()
+    }
 }
 
 impl super::Command for Args {
@@ -195,15 +215,13 @@ impl super::Command for Args {
                 (self.bucket, self.pattern)
             };
 
-        let pattern = {
-            Regex::new(&format!(
-                "{}{raw_pattern}",
-                if self.case_sensitive { "" } else { "(?i)" },
-            ))
-            .calm_expect(
-                "Invalid Regex provided. See https://docs.rs/regex/latest/regex/ for more info",
-            )
-        };
+        let pattern = Pattern::new(
+            &raw_pattern,
+            patterns::Options::new()
+                .regex(self.regex)
+                .case_sensitive(self.case_sensitive)
+                .partial(true),
+        )?;
 
         let matching_buckets: Vec<Bucket> =
             if let Some(Ok(bucket)) = bucket.map(|name| Bucket::from_name(ctx, name)) {
@@ -212,43 +230,79 @@ impl super::Command for Args {
                 Bucket::list_all(ctx)?
             };
 
-        let mut matches: Sections<_> = matching_buckets
+        let mut matches = matching_buckets
             .par_iter()
-            .filter_map(
-                |bucket| match bucket.matches(ctx, self.installed, &pattern, self.mode) {
-                    Ok(manifest) => {
-                        let sections = manifest
-                            .into_par_iter()
-                            .filter_map(|manifest| {
-                                parse_output(
-                                    &manifest,
-                                    ctx,
-                                    unsafe { manifest.bucket() },
-                                    self.installed,
-                                    &pattern,
-                                    self.mode,
-                                    Architecture::ARCH,
-                                )
-                            })
-                            .collect::<Vec<_>>();
-
-                        if sections.is_empty() {
-                            None
-                        } else {
-                            let section = Section::new(Children::from(sections))
-                                .with_title(format!("'{}' bucket:", bucket.name()));
-
-                            Some(section)
-                        }
-                    }
-                    _ => None,
-                },
-            )
-            .collect();
+            .filter_map(|bucket| {
+                if let Some(index) = BucketIndex::fresh(ctx, bucket) {
+                    return Some(index.entries);
+                }
+
+                // Bucket matching only checks names and binaries, so searching other fields requires every manifest
+                let manifests = if self.fields.is_empty() {
+                    bucket
+                        .matches(ctx, self.installed, pattern.as_regex(), self.mode)
+                        .ok()
+                } else {
+                    bucket.list_packages().ok()
+                }?;
+
+                let entries = manifests
+                    .iter()
+                    .map(|manifest| Entry::new(unsafe { manifest.name() }, bucket.name(), manifest))
+                    .collect::<Vec<_>>();
+
+                Some(entries)
+            })
+            .flatten()
+            .filter_map(|entry| {
+                parse_output(
+                    &entry,
+                    ctx,
+                    self.installed,
+                    &pattern,
+                    self.mode,
+                    &self.fields,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if self.ranked {
+            let known_buckets = ctx
+                .known_buckets()
+                .into_iter()
+                .map(|(name, _)| (*name).to_string())
+                .collect_vec();
 
-        matches.par_sort();
+            matches.par_sort_by(|a, b| a.cmp_relevance(b, &known_buckets));
+        } else {
+            matches.par_sort_by(|a, b| a.bucket.cmp(&b.bucket));
+        }
 
-        print!("{matches}");
+        if let Some(limit) = self.limit {
+            matches.truncate(limit);
+        }
+
+        let format = Format::resolve(self.format, self.json);
+
+        if !format.is_table() {
+            format.print_rows(&matches, None)?;
+        } else if self.flat {
+            if matches.is_empty() {
+                println!("No results found");
+            }
+
+            let output = matches
+                .into_iter()
+                .map(|search_match| {
+                    let output = search_match.with_show_bucket(true).to_string();
+                    format!("{}\n", output.trim_end())
+                })
+                .collect::<String>();
+
+            pager::print(output)?;
+        } else {
+            pager::print(Self::group_by_bucket(matches))?;
+        }
 
         Ok(())
     }
@@ -1,24 +1,31 @@
-use std::{fmt::Write, sync::Arc};
+use std::fmt::Write;
 
 use clap::{Parser, ValueEnum};
-use parking_lot::Mutex;
+use itertools::Itertools;
 use quork::prelude::*;
 use rayon::prelude::*;
+use serde::Serialize;
 use serde_json::Value;
 
 use sprinkles::{
     buckets::Bucket,
+    config,
     contexts::ScoopContext,
     packages::models::install,
     progress::{indicatif::ProgressBar, style},
 };
 
 use crate::{
-    models::status::Info,
+    commands::Query,
+    errors::Error,
+    models::{remote::Remote, status::Info},
     output::{
+        format::{Format, Render},
         sectioned::{Children, Section},
         structured::Structured,
+        theme::Role,
     },
+    wrappers::version::Availability,
 };
 
 #[derive(Debug, Copy, Clone, ValueEnum, ListVariants)]
@@ -28,12 +35,22 @@ enum Command {
     Apps,
 }
 
+/// The result of checking a single section
+enum Section {
+    Scoop(bool),
+    Buckets(Vec<String>),
+    Apps(Vec<Info>),
+}
+
 #[derive(Debug, Clone, Parser)]
 /// Show status and check for new app versions
 pub struct Args {
     #[clap(from_global)]
     json: bool,
 
+    #[clap(from_global)]
+    format: Option<Format>,
+
     #[clap(from_global)]
     verbose: bool,
 
@@ -42,14 +59,174 @@ pub struct Args {
 
     #[clap(short = 'H', long, help = "Ignore held packages")]
     ignore_held: bool,
+
+    #[clap(
+        short = 'A',
+        long,
+        help = "Check every bucket for the highest available version, rather than only the bucket each app was installed from"
+    )]
+    all_buckets: bool,
+
+    #[clap(
+        long,
+        help = "Exit with a non-zero code for each kind of problem found: 33 if Scoop is outdated, 34 if buckets are outdated, 36 if apps are outdated, 40 if dependencies are missing, 48 if installs failed. Multiple problems add together (i.e 39 for Scoop, buckets and apps)"
+    )]
+    exit_code: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// A problem reported by `--exit-code`, with its flag in the exit code
+enum Problem {
+    ScoopOutdated = 1,
+    BucketsOutdated = 2,
+    AppsOutdated = 4,
+    MissingDependencies = 8,
+    FailedInstalls = 16,
+}
+
+impl Problem {
+    fn description(self) -> &'static str {
+        match self {
+            Problem::ScoopOutdated => "Scoop is outdated",
+            Problem::BucketsOutdated => "buckets are outdated",
+            Problem::AppsOutdated => "apps are outdated",
+            Problem::MissingDependencies => "dependencies are missing",
+            Problem::FailedInstalls => "installs failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+/// The status of each checked section of Scoop
+pub struct Output {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether Scoop itself is out of date
+    scoop: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The names of the outdated buckets
+    buckets: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The apps which are outdated, or have other issues
+    packages: Option<Vec<Info>>,
+
+    #[serde(skip)]
+    verbose: bool,
+    #[serde(skip)]
+    exit_code: bool,
+}
+
+impl Output {
+    /// The problems found in the checked sections
+    fn problems(&self) -> Vec<Problem> {
+        let packages = self.packages.as_deref().unwrap_or_default();
+
+        [
+            (self.scoop == Some(true), Problem::ScoopOutdated),
+            (
+                self.buckets
+                    .as_ref()
+                    .is_some_and(|buckets| !buckets.is_empty()),
+                Problem::BucketsOutdated,
+            ),
+            (
+                packages
+                    .iter()
+                    .any(|package| package.status == Availability::Update),
+                Problem::AppsOutdated,
+            ),
+            (
+                packages
+                    .iter()
+                    .any(|package| !package.missing_dependencies.is_empty()),
+                Problem::MissingDependencies,
+            ),
+            (
+                packages.iter().any(|package| {
+                    package
+                        .info
+                        .as_ref()
+                        .is_some_and(|info| info.contains("Install failed"))
+                }),
+                Problem::FailedInstalls,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(found, problem)| found.then_some(problem))
+        .collect()
+    }
+}
+
+impl Render for Output {
+    fn render_table(&self, f: &mut dyn Write) -> anyhow::Result<()> {
+        if let Some(is_outdated) = self.scoop {
+            if is_outdated {
+                writeln!(
+                    f,
+                    "{}",
+                    Role::Warning.style(
+                        "Scoop is out of date. Run `scoop update` to get the latest changes."
+                    )
+                )?;
+            } else {
+                writeln!(f, "Scoop app is up to date.")?;
+            }
+        }
+
+        if let Some(ref outdated_buckets) = self.buckets {
+            if outdated_buckets.is_empty() {
+                writeln!(f, "All buckets up to date.")?;
+            } else if self.verbose {
+                let title = format!("{} outdated buckets:", outdated_buckets.len());
+
+                let section =
+                    Section::new(Children::from(outdated_buckets.clone())).with_title(title);
+
+                writeln!(f, "{section}")?;
+            } else {
+                writeln!(
+                    f,
+                    "{}",
+                    Role::Warning.style(
+                        "Bucket(s) are out of date. Run `scoop update` to get the latest changes."
+                    )
+                )?;
+            }
+        }
+
+        if let Some(ref packages) = self.packages {
+            if packages.is_empty() {
+                writeln!(f, "All packages are okay and up to date.")?;
+            } else {
+                let table = Structured::new(packages)
+                    .with_global_options()?
+                    .with_max_length(30);
+
+                write!(f, "{table}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rows(&self) -> anyhow::Result<Value> {
+        // Row based formats can only represent the packages table
+        Ok(serde_json::to_value(
+            self.packages.as_deref().unwrap_or_default(),
+        )?)
+    }
 }
 
-impl super::Command for Args {
-    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
-        let value = Arc::new(Mutex::new(Value::default()));
+impl Query for Args {
+    type Output = Output;
 
-        let pb = ProgressBar::new(3).with_style(style(None, None));
+    fn format(&self) -> Format {
+        Format::resolve(self.format, self.json)
+    }
 
+    async fn query(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Output> {
         let commands: &[Command] = {
             if self.only.is_empty() {
                 &Command::VARIANTS
@@ -58,110 +235,71 @@ impl super::Command for Args {
             }
         };
 
-        let outputs = commands.iter().map(|command| {
-            let command = *command;
-            let this = self.clone();
+        let pb = ProgressBar::new(commands.len() as u64).with_style(style(None, None));
+
+        let mut output = Output {
+            verbose: self.verbose,
+            exit_code: self.exit_code,
+            ..Default::default()
+        };
+
+        // Check the sections concurrently, so the bucket and app scans don't wait on the network
+        let sections = commands.iter().map(|command| {
             let pb = pb.clone();
-            let value = value.clone();
-            async move {
-                let mut output = String::new();
+            let this = &self;
 
-                match command {
-                    Command::Scoop => this.handle_scoop(ctx, &value, &mut output).await?,
-                    Command::Buckets => this.handle_buckets(ctx, &value, &mut output)?,
-                    Command::Apps => this.handle_packages(ctx, &value, &mut output)?,
+            async move {
+                let section = match command {
+                    Command::Scoop => Section::Scoop(ctx.outdated().await?),
+                    Command::Buckets => Section::Buckets(Self::outdated_buckets(ctx)?),
+                    Command::Apps => Section::Apps(this.packages(ctx)?),
                 };
 
                 pb.inc(1);
 
-                anyhow::Ok(output)
+                anyhow::Ok(section)
             }
         });
 
-        let outputs = futures::future::try_join_all(outputs).await?;
-
-        pb.finish_and_clear();
-
-        if self.json {
-            let output = serde_json::to_string(&value.lock().clone())?;
-            println!("{output}");
-        } else {
-            for output in outputs {
-                print!("{output}");
+        for section in futures::future::try_join_all(sections).await? {
+            match section {
+                Section::Scoop(outdated) => output.scoop = Some(outdated),
+                Section::Buckets(buckets) => output.buckets = Some(buckets),
+                Section::Apps(packages) => output.packages = Some(packages),
             }
         }
 
-        Ok(())
+        pb.finish_and_clear();
+
+        Ok(output)
     }
-}
 
-impl Args {
-    async fn handle_scoop(
-        &self,
-        ctx: &impl ScoopContext,
-        value: &Mutex<Value>,
-        output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
-        let is_outdated = ctx.outdated().await?;
-
-        if self.json {
-            value.lock()["scoop"] = serde_json::to_value(is_outdated)?;
+    fn check(output: &Output) -> anyhow::Result<()> {
+        let problems = output.problems();
+
+        if !output.exit_code || problems.is_empty() {
             return Ok(());
-        } else if is_outdated {
-            writeln!(
-                output,
-                "{}",
-                console::style(
-                    "Scoop is out of date. Run `scoop update` to get the latest changes."
-                )
-                .yellow()
-            )?;
-        } else {
-            writeln!(output, "Scoop app is up to date.")?;
         }
 
-        Ok(())
+        let flags = problems
+            .iter()
+            .fold(0, |flags, problem| flags | *problem as u8);
+        let message = problems
+            .iter()
+            .map(|problem| problem.description())
+            .join(", ");
+
+        Err(Error::status(flags, message).into())
     }
+}
 
-    fn handle_buckets(
-        &self,
-        ctx: &impl ScoopContext,
-        value: &Mutex<Value>,
-        output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
+impl Args {
+    fn outdated_buckets(ctx: &impl ScoopContext) -> anyhow::Result<Vec<String>> {
         let buckets = Bucket::list_all(ctx)?;
 
-        // Handle buckets
-        if self.verbose || self.json {
-            let outdated_buckets = buckets
-                .par_iter()
-                .filter_map(|bucket| {
-                    bucket.outdated().ok().and_then(|outdated| {
-                        if outdated {
-                            Some(bucket.name().to_string())
-                        } else {
-                            None
-                        }
-                    })
-                })
-                .collect::<Vec<_>>();
-
-            if self.json {
-                value.lock()["buckets"] = serde_json::to_value(&outdated_buckets)?;
-                return Ok(());
-            }
-
-            if outdated_buckets.is_empty() {
-                writeln!(output, "All buckets up to date.")?;
-            } else {
-                let title = format!("{} outdated buckets:", outdated_buckets.len());
-
-                let section = Section::new(Children::from(outdated_buckets)).with_title(title);
-
-                writeln!(output, "{section}")?;
-            }
-        } else {
-            let buckets_outdated = buckets.par_iter().any(|bucket| {
+        let outdated_buckets = buckets
+            .par_iter()
+            .filter(|bucket| {
                 bucket.outdated().unwrap_or_else(|err| {
                     eprintln!("Failed to check bucket: {}", bucket.name());
                     error!(
@@ -171,44 +309,34 @@ impl Args {
                     );
                     false
                 })
-            });
-
-            if buckets_outdated {
-                writeln!(
-                    output,
-                    "{}",
-                    console::style(
-                        "Bucket(s) are out of date. Run `scoop update` to get the latest changes."
-                    )
-                    .yellow()
-                )?;
-            } else {
-                writeln!(output, "All buckets up to date.")?;
-            }
-        }
+            })
+            .map(|bucket| bucket.name().to_string())
+            .collect();
 
-        Ok(())
+        Ok(outdated_buckets)
     }
 
-    fn handle_packages(
-        &self,
-        ctx: &impl ScoopContext,
-        value: &Mutex<Value>,
-        output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
+    fn packages(&self, ctx: &impl ScoopContext) -> anyhow::Result<Vec<Info>> {
         let apps = install::Manifest::list_all_unchecked(ctx)?;
 
         debug!("Checking {} apps", apps.len());
 
+        let buckets = if self.all_buckets {
+            Some(Bucket::list_all(ctx)?)
+        } else {
+            None
+        };
+
         let mut invalid_apps = apps
             .par_iter()
             .flat_map(|app| -> anyhow::Result<Info> {
                 if let Some(bucket) = &app.bucket {
                     let local_manifest = app.get_manifest(ctx)?;
-                    // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
-                    let bucket = Bucket::from_name(ctx, bucket)?;
+                    let remote =
+                        Remote::find(ctx, buckets.as_deref(), unsafe { app.name() }, bucket)?;
 
-                    match Info::from_manifests(ctx, &local_manifest, &bucket) {
+                    match Info::from_manifests(ctx, &local_manifest, &remote.manifest) {
+                        Ok(info) if self.all_buckets => Ok(info.with_bucket(&remote)),
                         Ok(info) => Ok(info),
                         Err(err) => {
                             error!(
@@ -238,56 +366,52 @@ impl Args {
                 };
 
                 // Filter out apps that are okay
-                info_exists || missing_deps || app.current != app.available
+                info_exists || missing_deps || app.status != Availability::UpToDate
             })
             .collect::<Vec<_>>();
 
         invalid_apps.dedup();
+        invalid_apps.par_sort_by(|a, b| a.name.cmp(&b.name));
 
-        if self.json {
-            value.lock()["packages"] = serde_json::to_value(&invalid_apps)?;
-            return Ok(());
-        }
+        Ok(invalid_apps)
+    }
+}
 
-        if invalid_apps.is_empty() {
-            writeln!(output, "All packages are okay and up to date.")?;
-        } else {
-            invalid_apps.par_sort_by(|a, b| a.name.cmp(&b.name));
-
-            let values = invalid_apps
-                .par_iter()
-                .map(serde_json::to_value)
-                .collect::<Result<Vec<_>, _>>()?;
-
-            // if self.json {
-            //     let output = serde_json::to_string_pretty(&values)?;
-
-            //     println!("{output}");
-            // } else {
-            // TODO: Add a better way to add colours than this
-            // TODO: p.s this doesnt work atm
-            // use owo_colors::OwoColorize;
-            // let values = values
-            //     .into_par_iter()
-            //     .map(|mut value| {
-            //         if let Some(current) = value.get_mut("Current") {
-            //             *current = current.as_str().unwrap().red().to_string().into();
-            //         }
-
-            //         if let Some(available) = value.get_mut("Available") {
-            //             *available = available.as_str().unwrap().green().to_string().into();
-            //         }
-
-            //         value
-            //     })
-            //     .collect::<Vec<_>>();
-
-            let outputs = Structured::new(&values).with_max_length(30);
-
-            write!(output, "{outputs}")?;
-            // }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problems() {
+        let package = Info {
+            name: "sfsu".into(),
+            current: "1.0.0".into(),
+            available: "1.1.0".into(),
+            status: Availability::Update,
+            bucket: None,
+            bucket_changed: None,
+            missing_dependencies: vec![],
+            info: Some("Install failed".into()),
+        };
 
-        Ok(())
+        let output = Output {
+            scoop: Some(true),
+            buckets: None,
+            packages: Some(vec![package]),
+            verbose: false,
+            exit_code: true,
+        };
+
+        assert_eq!(
+            output.problems(),
+            [
+                Problem::ScoopOutdated,
+                Problem::AppsOutdated,
+                Problem::FailedInstalls
+            ]
+        );
+
+        let error = Args::check(&output).unwrap_err();
+        assert_eq!(Error::exit_code_of(&error), 32 + 1 + 4 + 16);
     }
 }
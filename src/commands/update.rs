@@ -16,13 +16,32 @@ use sprinkles::{
     },
 };
 
-use crate::output::sectioned::{Children, Section};
+use crate::{
+    models::{
+        index::BucketIndex,
+        plan::{Operation, Plan},
+    },
+    output::{
+        format::Format,
+        sectioned::{Children, Section},
+        theme::eprintln_styled,
+    },
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Update Scoop and Scoop buckets
 pub struct Args {
     #[clap(short, long, help = "Show commit messages for each update")]
     changelog: bool,
+
+    #[clap(from_global)]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    json: bool,
+
+    #[clap(from_global)]
+    format: Option<Format>,
 }
 
 impl super::Command for Args {
@@ -31,6 +50,12 @@ impl super::Command for Args {
 
         let buckets = Bucket::list_all(ctx)?;
 
+        if self.dry_run {
+            let plan = Self::plan(ctx, &buckets)?;
+
+            return Format::resolve(self.format, self.json).render(&plan);
+        }
+
         let longest_bucket_name = buckets
             .iter()
             .map(|bucket| bucket.name().len())
@@ -64,6 +89,22 @@ impl super::Command for Args {
 
         let bucket_changelogs = self.update_buckets(ctx, &outdated_buckets)?;
 
+        // Build the index of every bucket whose index is missing or stale.
+        // Only manifests changed since the last build are re-parsed, so this is cheap when little has changed
+        let index_results = outdated_buckets
+            .par_iter()
+            .map(|(bucket, _)| (bucket.name(), BucketIndex::refresh(ctx, bucket)))
+            .collect::<Vec<_>>();
+
+        for (name, result) in index_results {
+            if let Err(error) = result {
+                eprintln_styled!(
+                    Warning,
+                    "Failed to update the search index for {name}: {error}"
+                );
+            }
+        }
+
         let mut scoop_config = ScoopConfig::load()?;
         scoop_config.update_last_update_time();
         scoop_config.save()?;
@@ -98,6 +139,41 @@ impl super::Command for Args {
 impl Args {
     const FINISH_MESSAGE: &'static str = "✅";
 
+    fn plan(ctx: &impl ScoopContext, buckets: &[Bucket]) -> anyhow::Result<Plan> {
+        let mut plan = Plan::new();
+
+        let repo = ctx.open_repo().context("missing user repository")??;
+        if repo.outdated()? {
+            plan.push(Operation::GitPull {
+                path: ctx.apps_path().join("scoop").join("current"),
+            });
+        }
+
+        for bucket in buckets {
+            let name = bucket.name();
+            let outdated = bucket.open_repo()?.outdated()?;
+
+            if outdated {
+                plan.push(Operation::GitPull {
+                    path: ctx.buckets_path().join(name.as_ref()),
+                });
+            }
+
+            if outdated || BucketIndex::fresh(ctx, bucket).is_none() {
+                plan.push(Operation::WriteFile {
+                    path: BucketIndex::path(ctx, &name),
+                });
+            }
+        }
+
+        plan.push(Operation::SetConfig {
+            key: "last_update".into(),
+            value: chrono::Local::now().to_rfc3339(),
+        });
+
+        Ok(plan)
+    }
+
     fn update_scoop(
         &self,
         ctx: &impl ScoopContext,
@@ -4,6 +4,7 @@ use anyhow::Context;
 use clap::{Parser, ValueEnum};
 use rayon::prelude::*;
 use sprinkles::{
+    buckets::Bucket,
     config,
     contexts::ScoopContext,
     hash::Hash,
@@ -14,10 +15,10 @@ use sprinkles::{
 };
 
 use crate::{
-    calm_panic::CalmUnwrap,
-    errors::{RecoverableError, RecoverableResult},
+    errors::{Error, RecoverableError, RecoverableResult},
     limits::RateLimiter,
-    output::colours::{eprintln_green, eprintln_red, eprintln_yellow},
+    output::theme::eprintln_styled,
+    patterns::{self, Pattern},
 };
 
 impl RecoverableError for vt3::error::VtError {
@@ -92,9 +93,16 @@ fn extract_info(value: &serde_json::Value) -> anyhow::Result<(u64, u64)> {
 #[derive(Debug, Clone, Parser)]
 /// Scan a file with `VirusTotal`
 pub struct Args {
-    // TODO: Use manifest reference and -a flag for scanning installed apps
-    #[clap(help = "The apps to scan for viruses")]
-    apps: Vec<package::Reference>,
+    #[clap(
+        help = "The apps to scan for viruses. Glob patterns (i.e `python*`) match every app in the searched buckets, or with `--all`, every installed app"
+    )]
+    apps: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Treat the apps as regex patterns, using Rust Regex syntax"
+    )]
+    regex: bool,
 
     #[clap(
         short,
@@ -120,13 +128,126 @@ pub struct Args {
     )]
     arch: Architecture,
 
-    #[clap(short = 'A', long, help = "Scan all installed apps")]
+    #[clap(
+        short = 'A',
+        long,
+        help = "Scan all installed apps, or only those matching the provided patterns"
+    )]
     all: bool,
 
     #[clap(from_global)]
     json: bool,
 }
 
+impl Args {
+    fn pattern(&self, pattern: &str) -> Result<Pattern, Error> {
+        Pattern::new(
+            pattern,
+            patterns::Options::new()
+                .regex(self.regex)
+                .case_sensitive(self.case_sensitive),
+        )
+    }
+
+    /// List the manifests of the installed apps, filtered by the provided patterns if any
+    fn installed_manifests(&self, ctx: &impl ScoopContext) -> anyhow::Result<Vec<Manifest>> {
+        let patterns = self
+            .apps
+            .iter()
+            .map(|app| self.pattern(app))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        #[allow(clippy::redundant_closure)]
+        let manifests = ctx
+            .installed_apps()?
+            .into_par_iter()
+            .filter(|path| {
+                patterns.is_empty()
+                    || path.file_name().is_some_and(|name| {
+                        let name = name.to_string_lossy();
+                        patterns.iter().any(|pattern| pattern.is_match(&name))
+                    })
+            })
+            .map(|path| path.join("current").join("manifest.json"))
+            .filter(|path| path.exists())
+            // The closure is redundant, but it's necessary to avoid a rust-analyzer error
+            .map(|path| Manifest::from_path(path))
+            .collect::<Result<_, _>>()?;
+
+        Ok(manifests)
+    }
+
+    /// Resolve the provided apps to manifests
+    ///
+    /// Patterns are matched against every manifest in the searched buckets,
+    /// and anything else is treated as a package reference
+    async fn resolve_manifests(
+        &self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> anyhow::Result<Vec<Manifest>> {
+        let (patterns, references): (Vec<_>, Vec<_>) = self
+            .apps
+            .iter()
+            .partition(|app| self.regex || patterns::is_glob(app));
+
+        let mut manifests = vec![];
+
+        if !patterns.is_empty() {
+            let patterns = patterns
+                .into_iter()
+                .map(|app| self.pattern(app))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let buckets = if let Some(bucket) = &self.bucket {
+                vec![Bucket::from_name(ctx, bucket)?]
+            } else {
+                Bucket::list_all(ctx)?
+            };
+
+            let matches = buckets
+                .par_iter()
+                .map(|bucket| -> anyhow::Result<Vec<Manifest>> {
+                    Ok(bucket
+                        .list_packages()?
+                        .into_iter()
+                        .filter(|manifest| {
+                            let name = unsafe { manifest.name() };
+                            patterns.iter().any(|pattern| pattern.is_match(name))
+                        })
+                        .collect())
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            manifests.extend(matches.into_iter().flatten());
+        }
+
+        let references = references
+            .into_iter()
+            .map(|app| {
+                let mut reference = app.parse::<package::Reference>()?;
+                if let Some(bucket) = &self.bucket {
+                    reference.set_bucket(bucket.clone())?;
+                }
+
+                anyhow::Ok(reference)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let references = references
+            .iter()
+            .map(|reference| async move { reference.list_manifests(ctx).await });
+
+        manifests.extend(
+            futures::future::try_join_all(references)
+                .await?
+                .into_iter()
+                .flatten(),
+        );
+
+        Ok(manifests)
+    }
+}
+
 impl super::Command for Args {
     const BETA: bool = true;
 
@@ -135,32 +256,18 @@ impl super::Command for Args {
         ctx: &impl ScoopContext<Config = config::Scoop>,
     ) -> Result<(), anyhow::Error> {
         let config = ctx.config();
-        let api_key = config.virustotal_api_key.clone().calm_expect(
-            "No virustotal api key found.\n  Get one at https://www.virustotal.com/gui/my-apikey and set with\n  scoop config virustotal_api_key <API key>",
-        );
+        let api_key = config.virustotal_api_key.clone().ok_or_else(|| {
+            Error::invalid_input(
+                "No virustotal api key found.\n  Get one at https://www.virustotal.com/gui/my-apikey and set with\n  scoop config virustotal_api_key <API key>",
+            )
+        })?;
 
         let client = vt3::VtClient::new(&api_key).user_agent(USER_AGENT);
 
-        #[allow(clippy::redundant_closure)]
         let manifests = if self.all {
-            ctx.installed_apps()?
-                .into_par_iter()
-                .map(|path| path.join("current").join("manifest.json"))
-                .filter(|path| path.exists())
-                // The closure is redundant, but it's necessary to avoid a rust-analyzer error
-                .map(|path| Manifest::from_path(path))
-                .collect::<Result<_, _>>()?
+            self.installed_manifests(ctx)?
         } else {
-            let manifests = self
-                .apps
-                .iter()
-                .map(|reference| async move { reference.list_manifests(ctx).await });
-
-            futures::future::try_join_all(manifests)
-                .await?
-                .into_par_iter()
-                .flatten()
-                .collect::<Vec<_>>()
+            self.resolve_manifests(ctx).await?
         };
 
         let pb = ProgressBar::new(manifests.len() as u64)
@@ -270,9 +377,9 @@ impl Args {
         }
 
         match file_status {
-            Status::Malicious => eprintln_red!("{info}"),
-            Status::Suspicious => eprintln_yellow!("{info}"),
-            Status::Undetected => eprintln_green!("{info}"),
+            Status::Malicious => eprintln_styled!(Error, "{info}"),
+            Status::Suspicious => eprintln_styled!(Warning, "{info}"),
+            Status::Undetected => eprintln_styled!(Success, "{info}"),
         };
 
         Ok(())
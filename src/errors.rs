@@ -1,3 +1,180 @@
+//! Error handling
+//!
+//! Errors which end the program are reported once, in [`report`], and exit with the code of their [`Kind`].
+
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::output::{format::Format, theme::eprintln_styled};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// The kind of an error, which decides the program's exit code
+///
+/// | Code  | Kind            |
+/// | ----- | --------------- |
+/// | 1     | `general`       |
+/// | 2     | `invalid_input` |
+/// | 3     | `not_found`     |
+/// | 4     | `io`            |
+/// | 5     | `network`       |
+/// | 6     | `drift`         |
+/// | 33-63 | `status`        |
+pub enum Kind {
+    /// Any error without a more specific kind
+    General,
+    /// Invalid arguments, patterns or configuration
+    InvalidInput,
+    /// Nothing was found, i.e no matching packages, buckets or cache entries
+    NotFound,
+    /// Reading or writing the filesystem failed
+    Io,
+    /// A download or other network request failed
+    Network,
+    /// The current state does not match a lockfile or Scoopfile
+    Drift,
+    /// Problems were found by `status --exit-code`. Not a failure, but reported through the exit code
+    Status,
+}
+
+impl Kind {
+    #[must_use]
+    /// The exit code for this kind of error
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::General => 1,
+            Self::InvalidInput => 2,
+            Self::NotFound => 3,
+            Self::Io => 4,
+            Self::Network => 5,
+            Self::Drift => 6,
+            Self::Status => 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An error with a known [`Kind`]
+pub struct Error {
+    kind: Kind,
+    code: i32,
+    message: String,
+}
+
+impl Error {
+    #[must_use]
+    /// Create an error of the given kind
+    pub fn new(kind: Kind, message: impl Display) -> Self {
+        Self {
+            kind,
+            code: kind.exit_code(),
+            message: message.to_string(),
+        }
+    }
+
+    #[must_use]
+    /// Create an invalid input error
+    pub fn invalid_input(message: impl Display) -> Self {
+        Self::new(Kind::InvalidInput, message)
+    }
+
+    #[must_use]
+    /// Create a not found error
+    pub fn not_found(message: impl Display) -> Self {
+        Self::new(Kind::NotFound, message)
+    }
+
+    #[must_use]
+    /// Create a network error
+    pub fn network(message: impl Display) -> Self {
+        Self::new(Kind::Network, message)
+    }
+
+    #[must_use]
+    /// Create an error for the problems found by `status --exit-code`
+    ///
+    /// The exit code is 32 plus the sum of the problems' flags
+    pub fn status(problems: u8, message: impl Display) -> Self {
+        Self {
+            code: Kind::Status.exit_code() + i32::from(problems),
+            ..Self::new(Kind::Status, message)
+        }
+    }
+
+    fn find(error: &anyhow::Error) -> Option<&Self> {
+        error.chain().find_map(|cause| cause.downcast_ref::<Self>())
+    }
+
+    #[must_use]
+    /// Get the kind of an error
+    ///
+    /// Uses the first typed [`Error`] in its chain of causes.
+    /// Otherwise errors caused by an IO error are [`Kind::Io`], and anything else is [`Kind::General`]
+    pub fn kind_of(error: &anyhow::Error) -> Kind {
+        if let Some(error) = Self::find(error) {
+            return error.kind;
+        }
+
+        if error.chain().any(|cause| cause.is::<std::io::Error>()) {
+            Kind::Io
+        } else {
+            Kind::General
+        }
+    }
+
+    #[must_use]
+    /// Get the code to exit with for an error
+    pub fn exit_code_of(error: &anyhow::Error) -> i32 {
+        Self::find(error).map_or_else(|| Self::kind_of(error).exit_code(), |error| error.code)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    kind: Kind,
+    code: i32,
+    message: String,
+    causes: Vec<String>,
+}
+
+/// Print an error which ended the program to stderr, and get the code to exit with
+///
+/// Errors are printed as a JSON object if the output format is not a table
+pub fn report(error: &anyhow::Error, format: Format) -> i32 {
+    let kind = Error::kind_of(error);
+    let code = Error::exit_code_of(error);
+
+    if format.is_table() {
+        // Status problems have already been printed as part of the output
+        if kind != Kind::Status {
+            eprintln_styled!(Error, "{error:?}");
+        }
+    } else {
+        let report = Report {
+            kind,
+            code,
+            message: error.to_string(),
+            causes: error.chain().skip(1).map(ToString::to_string).collect(),
+        };
+
+        match serde_json::to_string(&serde_json::json!({ "error": report })) {
+            Ok(report) => eprintln!("{report}"),
+            Err(_) => eprintln!("{error:?}"),
+        }
+    }
+
+    code
+}
+
 pub trait RecoverableError {
     /// Checks if the error is recoverable
     fn recoverable(&self) -> bool;
@@ -33,3 +210,29 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn test_kind_of() {
+        let not_found = Err::<(), _>(Error::not_found("No cache entries found"))
+            .context("Failed to list the cache")
+            .unwrap_err();
+        assert_eq!(Error::kind_of(&not_found), Kind::NotFound);
+        assert_eq!(not_found.root_cause().to_string(), "No cache entries found");
+
+        let io = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Failed to read the manifest");
+        assert_eq!(Error::kind_of(&io), Kind::Io);
+
+        assert_eq!(Error::kind_of(&anyhow::anyhow!("oops")), Kind::General);
+
+        let status = anyhow::Error::from(Error::status(0b101, "Scoop and apps are outdated"));
+        assert_eq!(Error::kind_of(&status), Kind::Status);
+        assert_eq!(Error::exit_code_of(&status), 37);
+    }
+}
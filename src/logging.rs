@@ -5,7 +5,7 @@ use log::{Level, LevelFilter};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use sprinkles::contexts::ScoopContext;
 
-use crate::output::colours::{eprintln_red, eprintln_yellow};
+use crate::output::theme::eprintln_styled;
 
 pub mod panics;
 
@@ -118,8 +118,8 @@ impl log::Log for Logger {
     fn log(&self, record: &log::Record<'_>) {
         if self.enabled(record.metadata()) {
             match record.metadata().level() {
-                Level::Error => eprintln_red!("{}", record.args()),
-                Level::Warn => eprintln_yellow!("{}", record.args()),
+                Level::Error => eprintln_styled!(Error, "{}", record.args()),
+                Level::Warn => eprintln_styled!(Warning, "{}", record.args()),
                 _ => {
                     // TODO: Add a queue of sorts because this doesn't work well with multiple threads
                     writeln!(
@@ -5,8 +5,6 @@
     rust_2024_compatibility
 )]
 
-// TODO: Replace regex with glob
-
 mod calm_panic;
 mod commands;
 mod diagnostics;
@@ -15,6 +13,8 @@ mod limits;
 mod logging;
 mod models;
 mod output;
+mod patterns;
+mod settings;
 mod wrappers;
 
 use std::{
@@ -69,8 +69,6 @@ libgit2:{LIBGIT2_VERSION}"#
 #[macro_use]
 extern crate log;
 
-// TODO: Add dry-run option for debugging
-
 /// Scoop utilities that can replace the slowest parts of Scoop, and run anywhere from 30-100 times faster
 #[derive(Debug, Parser)]
 #[clap(about, long_about, version, long_version = versions::SFSU_LONG_VERSION, author)]
@@ -94,6 +92,42 @@ struct Args {
     )]
     json: bool,
 
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The format to print structured output in. Takes precedence over `--json`"
+    )]
+    format: Option<output::format::Format>,
+
+    #[clap(
+        long,
+        global = true,
+        value_delimiter = ',',
+        help = "The columns to show in tables, in order, as a comma separated list"
+    )]
+    columns: Option<Vec<String>>,
+
+    #[clap(long, global = true, help = "Hide the header row in tables")]
+    no_headers: bool,
+
+    #[clap(long, global = true, help = "Sort table rows by the given column")]
+    sort_by: Option<String>,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print long outputs directly, rather than through a pager"
+    )]
+    no_pager: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print the filesystem and git operations that would be performed, without performing them"
+    )]
+    dry_run: bool,
+
     #[clap(short, long, global = true, help = "Show more information in outputs")]
     verbose: bool,
 
@@ -137,11 +171,18 @@ impl TryFrom<&Args> for AnyContext {
 }
 
 #[tokio::main(flavor = "multi_thread")]
-async fn main() -> anyhow::Result<()> {
+async fn main() {
     logging::panics::handle();
 
     let args = Args::parse();
+    let format = output::format::Format::resolve(args.format, args.json);
+
+    if let Err(error) = run(args).await {
+        std::process::exit(errors::report(&error, format));
+    }
+}
 
+async fn run(args: Args) -> anyhow::Result<()> {
     let ctx: AnyContext = {
         cfg_if::cfg_if! {
             if #[cfg(feature = "contexts")] {
@@ -152,6 +193,14 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // The theme is fixed by the first styled output, so it must be set before anything is printed or logged
+    let (settings, settings_error) = match settings::Settings::load(&ctx) {
+        Ok(settings) => (settings, None),
+        Err(error) => (settings::Settings::default(), Some(error)),
+    };
+
+    output::theme::Theme::new(&settings.theme).set_global();
+
     // Spawn a task to cleanup logs in the background
     tokio::task::spawn_blocking({
         let ctx = ctx.clone();
@@ -160,6 +209,10 @@ async fn main() -> anyhow::Result<()> {
 
     Logger::init(&ctx, cfg!(debug_assertions) || args.verbose).await?;
 
+    if let Some(error) = settings_error {
+        warn!("{error:#}");
+    }
+
     if args.no_color || !std::io::stdout().is_terminal() {
         debug!("Colour disabled globally");
         console::set_colors_enabled(false);
@@ -167,6 +220,18 @@ async fn main() -> anyhow::Result<()> {
         COLOR_ENABLED.store(false, Ordering::Relaxed);
     }
 
+    if args.no_pager || !settings.pager {
+        debug!("Pager disabled");
+        output::pager::disable();
+    }
+
+    output::structured::TableOptions {
+        columns: args.columns,
+        no_headers: args.no_headers,
+        sort_by: args.sort_by,
+    }
+    .set_global();
+
     debug!("Running command: {:?}", args.command);
 
     args.command.run(&ctx).await?;
@@ -1,6 +1,13 @@
+pub mod depends;
 pub mod export;
+pub mod index;
 pub mod info;
+pub mod lock;
 pub mod min;
 #[cfg(not(feature = "v2"))]
 pub mod outdated;
+pub mod plan;
+pub mod remote;
+pub mod scoopfile;
+pub mod search;
 pub mod status;
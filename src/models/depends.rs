@@ -0,0 +1,524 @@
+//! Dependency graph resolution
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+};
+
+use rayon::prelude::*;
+use serde::Serialize;
+use sprinkles::{
+    buckets::Bucket,
+    contexts::ScoopContext,
+    packages::{
+        reference::{manifest, package},
+        CreateManifest, InstallManifest, Manifest,
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+/// A package in a dependency graph
+pub struct Node {
+    /// The name of the package
+    pub name: String,
+    /// The bucket the package was found in
+    pub bucket: String,
+    /// The version of the package
+    pub version: String,
+    /// Whether the package is installed
+    pub installed: bool,
+    /// The names of the packages this package depends on
+    pub depends: Vec<String>,
+    /// The names of the packages this package suggests
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggests: Vec<String>,
+}
+
+impl Node {
+    fn new(ctx: &impl ScoopContext, manifest: &Manifest, depends: Vec<String>) -> Self {
+        Self {
+            name: unsafe { manifest.name() }.to_string(),
+            bucket: unsafe { manifest.bucket() }.to_string(),
+            version: manifest.version.to_string(),
+            installed: manifest.is_installed(ctx, None),
+            depends,
+            suggests: suggestions(manifest),
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{}/{}@{}", self.bucket, self.name, self.version)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A fully resolved dependency graph for a package
+pub struct Graph {
+    /// The name of the package the graph was resolved for
+    pub root: String,
+    /// Every package in the graph, in the order they will be installed
+    pub order: Vec<Node>,
+    /// Dependencies which could not be found in any bucket
+    pub unresolved: Vec<String>,
+    /// Dependency cycles, each listed from the first package in the cycle back to itself
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl Graph {
+    #[must_use]
+    /// Walk the full `depends` graph of the provided manifest
+    pub fn resolve(ctx: &impl ScoopContext, root: Manifest) -> Self {
+        let root_name = unsafe { root.name() }.to_string();
+
+        let mut nodes = BTreeMap::new();
+        let mut unresolved = vec![];
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(manifest) = queue.pop_front() {
+            let mut depends = vec![];
+
+            for reference in dependencies(&manifest) {
+                let Some(dependency) = reference.first(ctx) else {
+                    let name = reference.to_string();
+                    if !unresolved.contains(&name) {
+                        unresolved.push(name.clone());
+                    }
+                    // Keep the edge, so the graph shows which package has the broken dependency
+                    depends.push(name);
+                    continue;
+                };
+
+                let name = unsafe { dependency.name() }.to_string();
+
+                let queued = queue
+                    .iter()
+                    .any(|manifest| unsafe { manifest.name() } == name);
+                if !nodes.contains_key(&name) && !queued && name != unsafe { manifest.name() } {
+                    queue.push_back(dependency);
+                }
+
+                depends.push(name);
+            }
+
+            let node = Node::new(ctx, &manifest, depends);
+            nodes.insert(node.name.clone(), node);
+        }
+
+        let edges = nodes
+            .iter()
+            .map(|(name, node)| (name.clone(), node.depends.clone()))
+            .collect();
+
+        let (order, cycles) = install_order(&edges, &root_name);
+
+        Self {
+            root: root_name,
+            order: order
+                .into_iter()
+                .filter_map(|name| nodes.remove(&name))
+                .collect(),
+            unresolved,
+            cycles,
+        }
+    }
+}
+
+impl Graph {
+    #[must_use]
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// Installed packages are filled, unresolved dependencies are red and dashed,
+    /// and suggestions are drawn as dashed edges
+    pub fn to_dot(&self) -> String {
+        let mut output = format!("digraph \"{}\" {{\n    node [shape=box];\n", self.root);
+
+        for node in &self.order {
+            let style = if node.installed {
+                ", style=filled, fillcolor=palegreen"
+            } else {
+                ""
+            };
+
+            _ = writeln!(
+                output,
+                "    \"{}\" [label=\"{}\"{style}];",
+                node.name,
+                node.label()
+            );
+        }
+
+        for name in &self.unresolved {
+            _ = writeln!(
+                output,
+                "    \"{name}\" [label=\"{name} (unresolved)\", style=\"filled,dashed\", color=red, fillcolor=mistyrose];"
+            );
+        }
+
+        for node in &self.order {
+            for dependency in &node.depends {
+                _ = writeln!(output, "    \"{}\" -> \"{dependency}\";", node.name);
+            }
+
+            for suggestion in &node.suggests {
+                _ = writeln!(
+                    output,
+                    "    \"{}\" -> \"{suggestion}\" [style=dashed, label=\"suggest\"];",
+                    node.name
+                );
+            }
+        }
+
+        output.push('}');
+        output
+    }
+
+    #[must_use]
+    /// Render the graph as a Mermaid flowchart
+    ///
+    /// Installed packages use the `installed` class, unresolved dependencies use the `unresolved` class,
+    /// and suggestions are drawn as dotted edges
+    pub fn to_mermaid(&self) -> String {
+        let mut output = String::from("graph TD\n");
+
+        for node in &self.order {
+            _ = writeln!(
+                output,
+                "    {}[\"{}\"]",
+                mermaid_id(&node.name),
+                node.label()
+            );
+        }
+
+        for name in &self.unresolved {
+            _ = writeln!(output, "    {}[\"{name} (unresolved)\"]", mermaid_id(name));
+        }
+
+        for node in &self.order {
+            for dependency in &node.depends {
+                _ = writeln!(
+                    output,
+                    "    {} --> {}",
+                    mermaid_id(&node.name),
+                    mermaid_id(dependency)
+                );
+            }
+
+            for suggestion in &node.suggests {
+                _ = writeln!(
+                    output,
+                    "    {} -.->|suggest| {}",
+                    mermaid_id(&node.name),
+                    mermaid_id(suggestion)
+                );
+            }
+        }
+
+        let installed = self
+            .order
+            .iter()
+            .filter(|node| node.installed)
+            .map(|node| mermaid_id(&node.name))
+            .collect::<Vec<_>>();
+
+        if !installed.is_empty() {
+            output.push_str("    classDef installed fill:#9f9,stroke:#393\n");
+            _ = writeln!(output, "    class {} installed", installed.join(","));
+        }
+
+        if !self.unresolved.is_empty() {
+            output.push_str("    classDef unresolved fill:#fdd,stroke:#c33,stroke-dasharray:5 5\n");
+            _ = writeln!(
+                output,
+                "    class {} unresolved",
+                self.unresolved
+                    .iter()
+                    .map(|name| mermaid_id(name))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+
+        output
+    }
+}
+
+/// Mermaid node ids cannot contain most punctuation, so replace it with underscores
+fn mermaid_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// List the names of the packages a manifest suggests
+///
+/// Suggestions are grouped by feature, and each group may be a single package or a list
+fn suggestions(manifest: &Manifest) -> Vec<String> {
+    let Some(ref suggest) = manifest.suggest else {
+        return vec![];
+    };
+
+    let mut suggestions = suggest
+        .values()
+        .flat_map(|references| references.clone().to_vec())
+        // Suggestions may be bucket qualified, i.e `extras/vcredist2022`
+        .map(|reference| {
+            reference
+                .rsplit('/')
+                .next()
+                .unwrap_or(&reference)
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    suggestions.sort_unstable();
+    suggestions.dedup();
+
+    suggestions
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A package which depends on another package
+pub struct Dependent {
+    /// The name of the package
+    pub name: String,
+    /// The bucket of the package
+    pub bucket: String,
+    /// The version of the package
+    pub version: String,
+    /// Whether the package is installed
+    pub installed: bool,
+}
+
+#[must_use]
+/// List the dependencies of a manifest as package references
+pub fn dependencies(manifest: &Manifest) -> Vec<package::Reference> {
+    manifest
+        .depends()
+        .into_iter()
+        .map(manifest::Reference::into_package_ref)
+        .collect()
+}
+
+#[must_use]
+/// Check if a manifest lists the given package in its `depends`
+pub fn depends_on(manifest: &Manifest, name: &str) -> bool {
+    dependencies(manifest).into_iter().any(|reference| {
+        reference
+            .name()
+            .is_some_and(|dependency| dependency.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Find the installed apps which depend on the given package
+///
+/// # Errors
+/// - The installed apps could not be listed
+pub fn installed_dependents(ctx: &impl ScoopContext, name: &str) -> anyhow::Result<Vec<Dependent>> {
+    let apps = ctx.installed_apps()?;
+
+    let mut dependents = apps
+        .par_iter()
+        .filter_map(|path| {
+            let current = path.join("current");
+
+            let manifest = Manifest::from_path(current.join("manifest.json")).ok()?;
+            if !depends_on(&manifest, name) {
+                return None;
+            }
+
+            let bucket = InstallManifest::from_path(current.join("install.json"))
+                .map(|install_manifest| install_manifest.get_source())
+                .unwrap_or_default();
+
+            Some(Dependent {
+                name: path.file_name()?.to_string_lossy().to_string(),
+                bucket,
+                version: manifest.version.to_string(),
+                installed: true,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    dependents.par_sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(dependents)
+}
+
+/// Find the manifests in every bucket which depend on the given package
+///
+/// # Errors
+/// - The buckets could not be listed
+/// - A bucket's manifests could not be listed
+pub fn bucket_dependents(ctx: &impl ScoopContext, name: &str) -> anyhow::Result<Vec<Dependent>> {
+    let buckets = Bucket::list_all(ctx)?;
+
+    let dependents = buckets
+        .par_iter()
+        .map(|bucket| -> anyhow::Result<Vec<Dependent>> {
+            let bucket_name = bucket.name();
+
+            let dependents = bucket
+                .list_packages()?
+                .into_iter()
+                .filter(|manifest| depends_on(manifest, name))
+                .map(|manifest| Dependent {
+                    name: unsafe { manifest.name() }.to_string(),
+                    bucket: bucket_name.to_string(),
+                    version: manifest.version.to_string(),
+                    installed: manifest.is_installed(ctx, Some(&bucket_name)),
+                })
+                .collect();
+
+            Ok(dependents)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut dependents = dependents.into_iter().flatten().collect::<Vec<_>>();
+
+    dependents.par_sort_by(|a, b| a.bucket.cmp(&b.bucket).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(dependents)
+}
+
+/// Sort the packages reachable from `root` so that every package comes after its dependencies
+///
+/// Returns the install order, and any cycles found along the way.
+/// Packages in a cycle are still included in the order, at the point the cycle was broken.
+fn install_order(
+    edges: &BTreeMap<String, Vec<String>>,
+    root: &str,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum State {
+        Visiting,
+        Visited,
+    }
+
+    fn visit(
+        name: &str,
+        edges: &BTreeMap<String, Vec<String>>,
+        states: &mut BTreeMap<String, State>,
+        stack: &mut Vec<String>,
+        order: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        match states.get(name) {
+            Some(State::Visited) => return,
+            Some(State::Visiting) => {
+                if let Some(start) = stack.iter().position(|n| n == name) {
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(name.to_string());
+                    cycles.push(cycle);
+                }
+                return;
+            }
+            None => {}
+        }
+
+        states.insert(name.to_string(), State::Visiting);
+        stack.push(name.to_string());
+
+        for dependency in edges.get(name).into_iter().flatten() {
+            visit(dependency, edges, states, stack, order, cycles);
+        }
+
+        stack.pop();
+        states.insert(name.to_string(), State::Visited);
+        order.push(name.to_string());
+    }
+
+    let mut states = BTreeMap::new();
+    let mut order = vec![];
+    let mut cycles = vec![];
+
+    visit(
+        root,
+        edges,
+        &mut states,
+        &mut vec![],
+        &mut order,
+        &mut cycles,
+    );
+
+    (order, cycles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(name, depends)| {
+                (
+                    (*name).to_string(),
+                    depends.iter().map(ToString::to_string).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_install_order() {
+        let edges = edges(&[
+            ("app", &["runtime", "lib"]),
+            ("lib", &["runtime"]),
+            ("runtime", &[]),
+        ]);
+
+        let (order, cycles) = install_order(&edges, "app");
+
+        assert_eq!(order, ["runtime", "lib", "app"]);
+        assert_eq!(cycles.len(), 0);
+    }
+
+    #[test]
+    fn test_graph_rendering() {
+        let node = |name: &str, installed, depends: &[&str], suggests: &[&str]| Node {
+            name: name.to_string(),
+            bucket: "main".to_string(),
+            version: "1.0".to_string(),
+            installed,
+            depends: depends.iter().map(ToString::to_string).collect(),
+            suggests: suggests.iter().map(ToString::to_string).collect(),
+        };
+
+        let graph = Graph {
+            root: "app".to_string(),
+            order: vec![
+                node("lib-a", true, &[], &[]),
+                node("app", false, &["lib-a", "extras/missing"], &["vcredist"]),
+            ],
+            unresolved: vec!["extras/missing".to_string()],
+            cycles: vec![],
+        };
+
+        let dot = graph.to_dot();
+        assert!(dot.contains(r#""lib-a" [label="main/lib-a@1.0", style=filled"#));
+        assert!(dot.contains(r#""app" [label="main/app@1.0"];"#));
+        assert!(dot.contains(r#""app" -> "lib-a";"#));
+        assert!(dot.contains(r#""app" -> "vcredist" [style=dashed"#));
+        assert!(dot.contains(r#""app" -> "extras/missing";"#));
+        assert!(dot.contains(r#""extras/missing" [label="extras/missing (unresolved)""#));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains(r#"lib_a["main/lib-a@1.0"]"#));
+        assert!(mermaid.contains("app --> lib_a"));
+        assert!(mermaid.contains("app -.->|suggest| vcredist"));
+        assert!(mermaid.contains("class lib_a installed"));
+        assert!(mermaid.contains(r#"extras_missing["extras/missing (unresolved)"]"#));
+        assert!(mermaid.contains("app --> extras_missing"));
+        assert!(mermaid.contains("class extras_missing unresolved"));
+    }
+
+    #[test]
+    fn test_install_order_cycles() {
+        let edges = edges(&[("app", &["a"]), ("a", &["b"]), ("b", &["a"])]);
+
+        let (order, cycles) = install_order(&edges, "app");
+
+        assert_eq!(order, ["b", "a", "app"]);
+        assert_eq!(cycles, [["a", "b", "a"]]);
+    }
+}
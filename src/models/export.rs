@@ -1,8 +1,12 @@
 //! Package export data
 
+use std::{collections::HashMap, path::Path};
+
 use chrono::{DateTime, Local, SecondsFormat};
+use itertools::Itertools;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use sprinkles::{
     buckets::{Bucket as SfsuBucket, Error as BucketError},
@@ -12,7 +16,122 @@ use sprinkles::{
     packages::Error as PackageError,
 };
 
-use super::min::Info;
+use crate::{
+    errors::Error,
+    output::{format::Render, theme::Role, WHITESPACE},
+    patterns::Pattern,
+};
+
+use super::min::{self, Info};
+
+/// Config keys which describe the exporting machine, rather than the user's preferences
+pub const SKIPPED_CONFIG_KEYS: &[&str] = &["last_update"];
+
+/// Config keys which hold credentials, and are redacted by default
+pub const SENSITIVE_CONFIG_KEYS: &[&str] = &["virustotal_api_key", "gh_token", "private_hosts"];
+
+/// The value redacted config values are replaced with
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, Default)]
+/// Which apps and buckets to export
+pub struct Filter {
+    /// Only export apps matching any of these patterns
+    pub apps: Vec<Pattern>,
+    /// Only export buckets matching any of these patterns
+    pub buckets: Vec<Pattern>,
+    /// Exclude held apps
+    pub exclude_held: bool,
+    /// Exclude apps whose install failed
+    pub exclude_failed: bool,
+}
+
+impl Filter {
+    fn matches_app(&self, app: &App) -> bool {
+        let notes = app.info.split(", ").collect::<Vec<_>>();
+
+        matches_any(&self.apps, &app.name)
+            && !(self.exclude_held && notes.contains(&min::HELD))
+            && !(self.exclude_failed && notes.contains(&min::INSTALL_FAILED))
+    }
+}
+
+/// Replace the values of the given config keys with [`REDACTED`]
+///
+/// Arrays and objects keep their shape, with every string in them redacted, so that the config can still be read.
+/// Numbers and booleans can't be redacted without changing their type, so those keys are removed instead.
+///
+/// If `sensitive` is set, the [`SENSITIVE_CONFIG_KEYS`] and any credentials in the proxy are redacted as well
+fn redact(config: &mut Map<String, Value>, keys: &[String], sensitive: bool) {
+    let sensitive_keys = if sensitive {
+        SENSITIVE_CONFIG_KEYS
+    } else {
+        &[]
+    };
+
+    for key in sensitive_keys
+        .iter()
+        .copied()
+        .chain(keys.iter().map(String::as_str))
+    {
+        match config.get_mut(key) {
+            Some(Value::Bool(_) | Value::Number(_)) => {
+                config.remove(key);
+            }
+            Some(value) => redact_value(value),
+            None => {}
+        }
+    }
+
+    if sensitive {
+        // Proxies are written as `[username:password@]host:port`
+        if let Some(Value::String(proxy)) = config.get_mut("proxy") {
+            if let Some((credentials, host)) = proxy.rsplit_once('@') {
+                if credentials.contains(':') {
+                    *proxy = format!("{REDACTED}@{host}");
+                }
+            }
+        }
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(string) => *string = REDACTED.to_string(),
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        Value::Object(map) => map.values_mut().for_each(redact_value),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+#[must_use]
+/// Check if a config value was redacted, or contains redacted values
+///
+/// Redacted values must never be imported or compared, as they would overwrite the real values
+pub fn is_redacted(value: &Value) -> bool {
+    match value {
+        Value::String(string) => string.contains(REDACTED),
+        Value::Array(values) => values.iter().any(is_redacted),
+        Value::Object(map) => map.values().any(is_redacted),
+        Value::Null | Value::Bool(_) | Value::Number(_) => false,
+    }
+}
+
+#[must_use]
+/// Redact a single config value if its key is sensitive, as described in [`redact`]
+///
+/// Used wherever a config value is shown, so that credentials are never printed
+pub fn redact_sensitive(key: &str, value: &Value) -> Value {
+    let mut config = Map::from_iter([(key.to_string(), value.clone())]);
+    redact(&mut config, &[], true);
+
+    config.remove(key).unwrap_or_default()
+}
+
+/// Check if the text matches any of the patterns, or there are no patterns
+fn matches_any(patterns: &[Pattern], text: &str) -> bool {
+    patterns.is_empty() || patterns.iter().any(|pattern| pattern.is_match(text))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// The export data
@@ -36,10 +155,12 @@ pub struct App {
     pub name: String,
     /// The source of the app, e.g. bucket name
     pub source: String,
+    #[serde(default)]
     /// The last time the app was updated
     pub updated: String,
     /// The version of the app
     pub version: String,
+    #[serde(default)]
     /// Additional information about the app
     pub info: String,
 }
@@ -52,8 +173,10 @@ pub struct Bucket {
     pub name: String,
     /// The source of the bucket (e.g. git URL)
     pub source: String,
+    #[serde(default)]
     /// The last time the bucket was updated
     pub updated: String,
+    #[serde(default)]
     /// The number of manifests in the bucket
     pub manifests: usize,
 }
@@ -81,6 +204,52 @@ impl Export {
             config: Some(config),
         })
     }
+
+    /// Remove the apps and buckets which don't match the filter
+    pub fn filter(&mut self, filter: &Filter) {
+        self.apps.retain(|app| filter.matches_app(app));
+        self.buckets
+            .retain(|bucket| matches_any(&filter.buckets, &bucket.name));
+    }
+
+    /// Serialize the export, redacting the config as described in [`redact`]
+    ///
+    /// # Errors
+    /// - The export could not be serialized
+    pub fn to_redacted_value(&self, keys: &[String], sensitive: bool) -> serde_json::Result<Value> {
+        let mut value = serde_json::to_value(self)?;
+
+        if let Some(Value::Object(config)) = value.get_mut("config") {
+            redact(config, keys, sensitive);
+        }
+
+        Ok(value)
+    }
+
+    /// Read an export file, from `sfsu export` or `scoop export`
+    ///
+    /// # Errors
+    /// - The file could not be read
+    /// - The file is not a valid export
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+
+        let contents = match bytes.as_slice() {
+            // Windows PowerShell redirects output as UTF-16
+            [0xFF, 0xFE, rest @ ..] => String::from_utf16_lossy(
+                &rest
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .collect_vec(),
+            ),
+            [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+            bytes => String::from_utf8_lossy(bytes).into_owned(),
+        };
+
+        serde_json::from_str(&contents).map_err(|error| {
+            Error::invalid_input(format!("Invalid export file {}: {error}", path.display())).into()
+        })
+    }
 }
 
 impl From<Info> for App {
@@ -125,3 +294,456 @@ impl TryFrom<SfsuBucket> for Bucket {
         })
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// A value which differs between the reference export and the compared export
+pub struct Change<T> {
+    /// The name of the app or bucket, or the config key
+    pub name: String,
+    /// The value in the reference export
+    pub reference: T,
+    /// The value in the compared export
+    pub actual: T,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+/// The differences between a reference export and another export
+pub struct Diff {
+    /// Apps in the reference, but not the compared export
+    pub missing_apps: Vec<App>,
+    /// Apps in the compared export, but not the reference
+    pub extra_apps: Vec<App>,
+    /// Apps installed at a different version
+    pub changed_versions: Vec<Change<String>>,
+    /// Buckets in the reference, but not the compared export
+    pub missing_buckets: Vec<Bucket>,
+    /// Buckets in the compared export, but not the reference
+    pub extra_buckets: Vec<Bucket>,
+    /// Buckets added from a different source
+    pub changed_sources: Vec<Change<String>>,
+    /// Config keys with a different value. Only compared if the reference includes the config.
+    /// Redacted values are not compared, and sensitive values are redacted
+    pub changed_config: Vec<Change<Option<Value>>>,
+}
+
+impl Diff {
+    /// Compare an export against a reference export
+    ///
+    /// Apps and buckets are matched by name, ignoring case
+    ///
+    /// # Errors
+    /// - Either config could not be serialized
+    pub fn new(reference: &Export, actual: &Export) -> serde_json::Result<Self> {
+        let mut diff = Self::default();
+
+        let actual_apps = by_name(&actual.apps, |app| &app.name);
+        let reference_apps = by_name(&reference.apps, |app| &app.name);
+
+        for app in &reference.apps {
+            match actual_apps.get(&app.name.to_lowercase()) {
+                None => diff.missing_apps.push(app.clone()),
+                Some(actual) if actual.version != app.version => {
+                    diff.changed_versions.push(Change {
+                        name: app.name.clone(),
+                        reference: app.version.clone(),
+                        actual: actual.version.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.extra_apps = actual
+            .apps
+            .iter()
+            .filter(|app| !reference_apps.contains_key(&app.name.to_lowercase()))
+            .cloned()
+            .collect();
+
+        let actual_buckets = by_name(&actual.buckets, |bucket| &bucket.name);
+        let reference_buckets = by_name(&reference.buckets, |bucket| &bucket.name);
+
+        for bucket in &reference.buckets {
+            match actual_buckets.get(&bucket.name.to_lowercase()) {
+                None => diff.missing_buckets.push(bucket.clone()),
+                Some(actual) if !same_source(&actual.source, &bucket.source) => {
+                    diff.changed_sources.push(Change {
+                        name: bucket.name.clone(),
+                        reference: bucket.source.clone(),
+                        actual: actual.source.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.extra_buckets = actual
+            .buckets
+            .iter()
+            .filter(|bucket| !reference_buckets.contains_key(&bucket.name.to_lowercase()))
+            .cloned()
+            .collect();
+
+        if let Some(ref reference_config) = reference.config {
+            let reference_config = config_map(Some(reference_config))?;
+            let actual_config = config_map(actual.config.as_ref())?;
+
+            diff.changed_config = reference_config
+                .keys()
+                .chain(actual_config.keys())
+                .unique()
+                .filter(|key| !SKIPPED_CONFIG_KEYS.contains(&key.as_str()))
+                .filter_map(|key| {
+                    let reference = reference_config.get(key).cloned();
+                    let actual = actual_config.get(key).cloned();
+
+                    if [&reference, &actual].into_iter().flatten().any(is_redacted) {
+                        return None;
+                    }
+
+                    let redact =
+                        |value: Option<Value>| value.map(|value| redact_sensitive(key, &value));
+
+                    (reference != actual).then(|| Change {
+                        name: key.clone(),
+                        reference: redact(reference),
+                        actual: redact(actual),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(diff)
+    }
+
+    #[must_use]
+    /// Check if there are no differences
+    pub fn is_empty(&self) -> bool {
+        self.missing_apps.is_empty()
+            && self.extra_apps.is_empty()
+            && self.changed_versions.is_empty()
+            && self.missing_buckets.is_empty()
+            && self.extra_buckets.is_empty()
+            && self.changed_sources.is_empty()
+            && self.changed_config.is_empty()
+    }
+}
+
+impl Render for Diff {
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()> {
+        if self.is_empty() {
+            writeln!(f, "No differences found.")?;
+            return Ok(());
+        }
+
+        let missing = Role::Error.style("-");
+        let extra = Role::Success.style("+");
+        let changed = Role::Warning.style("~");
+
+        writeln!(
+            f,
+            "{missing} missing, {extra} extra, {changed} changed, compared to the reference"
+        )?;
+
+        if !(self.missing_apps.is_empty()
+            && self.extra_apps.is_empty()
+            && self.changed_versions.is_empty())
+        {
+            writeln!(f, "\nApps:")?;
+
+            for app in &self.missing_apps {
+                writeln!(f, "{WHITESPACE}{missing} {} ({})", app.name, app.version)?;
+            }
+            for app in &self.extra_apps {
+                writeln!(f, "{WHITESPACE}{extra} {} ({})", app.name, app.version)?;
+            }
+            for change in &self.changed_versions {
+                writeln!(
+                    f,
+                    "{WHITESPACE}{changed} {}: {} -> {}",
+                    change.name, change.reference, change.actual
+                )?;
+            }
+        }
+
+        if !(self.missing_buckets.is_empty()
+            && self.extra_buckets.is_empty()
+            && self.changed_sources.is_empty())
+        {
+            writeln!(f, "\nBuckets:")?;
+
+            for bucket in &self.missing_buckets {
+                writeln!(
+                    f,
+                    "{WHITESPACE}{missing} {} ({})",
+                    bucket.name, bucket.source
+                )?;
+            }
+            for bucket in &self.extra_buckets {
+                writeln!(f, "{WHITESPACE}{extra} {} ({})", bucket.name, bucket.source)?;
+            }
+            for change in &self.changed_sources {
+                writeln!(
+                    f,
+                    "{WHITESPACE}{changed} {}: {} -> {}",
+                    change.name, change.reference, change.actual
+                )?;
+            }
+        }
+
+        if !self.changed_config.is_empty() {
+            writeln!(f, "\nConfig:")?;
+
+            let display = |value: &Option<Value>| match value {
+                None => "(unset)".to_string(),
+                Some(Value::String(value)) => value.clone(),
+                Some(value) => value.to_string(),
+            };
+
+            for change in &self.changed_config {
+                writeln!(
+                    f,
+                    "{WHITESPACE}{changed} {}: {} -> {}",
+                    change.name,
+                    display(&change.reference),
+                    display(&change.actual)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Index items by their name, ignoring case
+pub(crate) fn by_name<T>(items: &[T], name: impl Fn(&T) -> &String) -> HashMap<String, &T> {
+    items
+        .iter()
+        .map(|item| (name(item).to_lowercase(), item))
+        .collect()
+}
+
+/// Compare bucket sources, ignoring case, and a trailing `.git` or `/`
+pub(crate) fn same_source(a: &str, b: &str) -> bool {
+    let normalize = |source: &str| {
+        source
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .to_lowercase()
+    };
+
+    normalize(a) == normalize(b)
+}
+
+fn config_map(config: Option<&config::Scoop>) -> serde_json::Result<Map<String, Value>> {
+    let Some(config) = config else {
+        return Ok(Map::new());
+    };
+
+    match serde_json::to_value(config)? {
+        Value::Object(map) => Ok(map
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .collect()),
+        _ => Ok(Map::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, version: &str) -> App {
+        App {
+            name: name.into(),
+            source: "main".into(),
+            updated: String::new(),
+            version: version.into(),
+            info: String::new(),
+        }
+    }
+
+    fn bucket(name: &str, source: &str) -> Bucket {
+        Bucket {
+            name: name.into(),
+            source: source.into(),
+            updated: String::new(),
+            manifests: 0,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let reference = Export {
+            config: None,
+            apps: vec![app("git", "2.45.0"), app("sfsu", "1.14.0")],
+            buckets: vec![
+                bucket("main", "https://github.com/ScoopInstaller/Main"),
+                bucket("extras", "https://github.com/ScoopInstaller/Extras"),
+            ],
+        };
+        let actual = Export {
+            config: None,
+            apps: vec![app("Git", "2.44.0"), app("7zip", "24.07")],
+            buckets: vec![
+                bucket("main", "https://github.com/ScoopInstaller/Main.git"),
+                bucket("extras", "https://github.com/someone/Extras"),
+            ],
+        };
+
+        let diff = Diff::new(&reference, &actual).unwrap();
+
+        assert_eq!(
+            diff.missing_apps.iter().map(|app| &app.name).collect_vec(),
+            ["sfsu"]
+        );
+        assert_eq!(
+            diff.extra_apps.iter().map(|app| &app.name).collect_vec(),
+            ["7zip"]
+        );
+        assert_eq!(
+            diff.changed_versions,
+            [Change {
+                name: "git".into(),
+                reference: "2.45.0".into(),
+                actual: "2.44.0".into(),
+            }]
+        );
+        assert!(diff.missing_buckets.is_empty() && diff.extra_buckets.is_empty());
+        assert_eq!(
+            diff.changed_sources
+                .iter()
+                .map(|change| &change.name)
+                .collect_vec(),
+            ["extras"]
+        );
+        assert!(diff.changed_config.is_empty());
+
+        assert!(Diff::new(&reference, &reference).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut export = Export {
+            config: None,
+            apps: vec![
+                app("git", "2.45.0"),
+                App {
+                    info: format!("{}, {}", min::INSTALL_FAILED, min::HELD),
+                    ..app("gitui", "0.26.3")
+                },
+                app("sfsu", "1.14.0"),
+            ],
+            buckets: vec![
+                bucket("main", "https://github.com/ScoopInstaller/Main"),
+                bucket("extras", "https://github.com/ScoopInstaller/Extras"),
+            ],
+        };
+
+        export.filter(&Filter {
+            apps: vec![Pattern::new("git*", crate::patterns::Options::new()).unwrap()],
+            buckets: vec![Pattern::new("main", crate::patterns::Options::new()).unwrap()],
+            exclude_held: true,
+            exclude_failed: false,
+        });
+
+        assert_eq!(
+            export.apps.iter().map(|app| &app.name).collect_vec(),
+            ["git"]
+        );
+        assert_eq!(
+            export
+                .buckets
+                .iter()
+                .map(|bucket| &bucket.name)
+                .collect_vec(),
+            ["main"]
+        );
+    }
+
+    #[test]
+    fn test_redact_proxy() {
+        let mut config = Map::new();
+        config.insert("proxy".into(), "user:hunter2@proxy.example.com:8080".into());
+
+        redact(&mut config, &[], true);
+        assert_eq!(
+            config["proxy"],
+            format!("{REDACTED}@proxy.example.com:8080")
+        );
+
+        config.insert("proxy".into(), "currentuser@default".into());
+        config.insert("aria2-enabled".into(), true.into());
+
+        redact(&mut config, &["aria2-enabled".to_string()], true);
+        assert_eq!(config["proxy"], "currentuser@default");
+        assert!(!config.contains_key("aria2-enabled"));
+    }
+
+    #[test]
+    fn test_redact_keeps_types() {
+        let mut config = Map::new();
+        config.insert("gh_token".into(), "ghp_secret".into());
+        config.insert(
+            "private_hosts".into(),
+            serde_json::json!([{ "match": "example.com", "headers": "Authorization=secret" }]),
+        );
+
+        redact(&mut config, &[], true);
+
+        assert_eq!(config["gh_token"], REDACTED);
+        assert_eq!(
+            config["private_hosts"],
+            serde_json::json!([{ "match": REDACTED, "headers": REDACTED }])
+        );
+        assert!(config.values().all(is_redacted));
+    }
+
+    #[test]
+    fn test_diff_redacted_config() {
+        let export = |config| Export {
+            config: Some(serde_json::from_value(config).unwrap()),
+            apps: vec![],
+            buckets: vec![],
+        };
+
+        let reference = export(serde_json::json!({ "gh_token": REDACTED }));
+        let actual = export(serde_json::json!({ "gh_token": "ghp_secret" }));
+
+        assert!(Diff::new(&reference, &actual).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_sensitive_config() {
+        let export = |config| Export {
+            config: Some(serde_json::from_value(config).unwrap()),
+            apps: vec![],
+            buckets: vec![],
+        };
+
+        // e.g. a plain `scoop export`, which is never redacted
+        let reference = export(serde_json::json!({ "gh_token": "ghp_reference" }));
+        let actual = export(serde_json::json!({
+            "gh_token": "ghp_actual",
+            "proxy": "user:password@proxy.example.com:8080",
+        }));
+
+        let diff = Diff::new(&reference, &actual).unwrap();
+
+        assert_eq!(
+            diff.changed_config,
+            [
+                Change {
+                    name: "gh_token".into(),
+                    reference: Some(REDACTED.into()),
+                    actual: Some(REDACTED.into()),
+                },
+                Change {
+                    name: "proxy".into(),
+                    reference: None,
+                    actual: Some(format!("{REDACTED}@proxy.example.com:8080").into()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! On-disk index of bucket manifests, for fast searches
+//!
+//! Each bucket has its own index, keyed by the bucket's HEAD commit.
+//! An index is only used while the commit matches, and is rebuilt incrementally,
+//! only re-parsing manifests which have changed since the index was last built.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sprinkles::{
+    buckets::Bucket,
+    contexts::ScoopContext,
+    packages::{CreateManifest, InstallManifest, Manifest, MergeDefaults},
+    Architecture,
+};
+
+/// Bumped whenever the index format changes, to force a full rebuild
+const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The searchable information of a single manifest
+pub struct Entry {
+    /// The name of the package
+    pub name: String,
+    /// The bucket of the package
+    pub bucket: String,
+    /// The version of the package
+    pub version: String,
+    /// The description of the package
+    pub description: Option<String>,
+    /// The homepage of the package
+    pub homepage: Option<String>,
+    /// The license of the package
+    pub license: Option<String>,
+    /// The binaries of the package, for the current architecture
+    pub bins: Vec<String>,
+    /// The names of the shortcuts the package creates
+    pub shortcuts: Vec<String>,
+
+    /// When the manifest was last modified, in milliseconds since the unix epoch
+    modified: u64,
+}
+
+impl Entry {
+    #[must_use]
+    /// Create a new entry from the provided manifest
+    pub fn new(name: impl Display, bucket: impl Display, manifest: &Manifest) -> Self {
+        Self {
+            name: name.to_string(),
+            bucket: bucket.to_string(),
+            version: manifest.version.to_string(),
+            description: manifest.description.clone(),
+            homepage: manifest.homepage.as_ref().map(ToString::to_string),
+            license: manifest.license.as_ref().map(ToString::to_string),
+            bins: manifest
+                .architecture
+                .merge_default(manifest.install_config.clone(), Architecture::ARCH)
+                .bin
+                .map(|b| b.to_vec())
+                .unwrap_or_default(),
+            shortcuts: shortcut_names(manifest),
+            modified: 0,
+        }
+    }
+
+    #[must_use]
+    /// Check if the package is installed from this entry's bucket
+    pub fn is_installed(&self, ctx: &impl ScoopContext) -> bool {
+        let install_manifest = ctx
+            .apps_path()
+            .join(&self.name)
+            .join("current")
+            .join("install.json");
+
+        InstallManifest::from_path(install_manifest)
+            .is_ok_and(|install_manifest| install_manifest.get_source() == self.bucket)
+    }
+}
+
+/// List the names of the shortcuts a manifest creates, across every architecture
+///
+/// Shortcuts are listed as `[target, name, ...]`, either at the top level or per architecture
+fn shortcut_names(manifest: &Manifest) -> Vec<String> {
+    let Ok(manifest) = serde_json::to_value(manifest) else {
+        return vec![];
+    };
+
+    let architectures = manifest["architecture"]
+        .as_object()
+        .into_iter()
+        .flat_map(|architectures| architectures.values());
+
+    std::iter::once(&manifest)
+        .chain(architectures)
+        .filter_map(|config| config["shortcuts"].as_array())
+        .flatten()
+        .filter_map(|shortcut| shortcut[1].as_str())
+        .map(ToString::to_string)
+        .unique()
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The index of a single bucket
+pub struct BucketIndex {
+    version: u32,
+    /// The name of the bucket
+    pub bucket: String,
+    /// The bucket's HEAD commit when the index was built
+    pub commit: String,
+    /// The bucket's manifests
+    pub entries: Vec<Entry>,
+}
+
+impl BucketIndex {
+    #[must_use]
+    /// The path to the bucket's index file
+    pub fn path(ctx: &impl ScoopContext, bucket: &str) -> PathBuf {
+        ctx.persist_path()
+            .join("sfsu")
+            .join("index")
+            .join(format!("{bucket}.json"))
+    }
+
+    fn head(bucket: &Bucket) -> anyhow::Result<String> {
+        let repo = bucket.open_repo()?;
+        let latest_commit = repo.latest_commit()?;
+
+        Ok(latest_commit.id().to_string())
+    }
+
+    fn load(ctx: &impl ScoopContext, bucket: &str) -> Option<Self> {
+        let index = std::fs::read(Self::path(ctx, bucket)).ok()?;
+        let index: Self = serde_json::from_slice(&index).ok()?;
+
+        (index.version == INDEX_VERSION).then_some(index)
+    }
+
+    fn save(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let path = Self::path(ctx, &self.bucket);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+
+        Ok(())
+    }
+
+    #[must_use]
+    /// Load the bucket's index, if it was built at the bucket's current commit
+    pub fn fresh(ctx: &impl ScoopContext, bucket: &Bucket) -> Option<Self> {
+        let index = Self::load(ctx, &bucket.name())?;
+        let head = Self::head(bucket).ok()?;
+
+        (index.commit == head).then_some(index)
+    }
+
+    /// Bring the bucket's index up to date with the bucket's current commit
+    ///
+    /// Only manifests which have changed since the last build are re-parsed
+    ///
+    /// # Errors
+    /// - The bucket's repository could not be opened
+    /// - The bucket's manifests could not be listed
+    /// - The index could not be saved
+    pub fn refresh(ctx: &impl ScoopContext, bucket: &Bucket) -> anyhow::Result<Self> {
+        let name = bucket.name();
+        let head = Self::head(bucket)?;
+
+        let previous = Self::load(ctx, &name);
+
+        if let Some(previous) = previous.as_ref().filter(|index| index.commit == head) {
+            return Ok(previous.clone());
+        }
+
+        let previous: HashMap<String, Entry> = previous
+            .map(|index| {
+                index
+                    .entries
+                    .into_iter()
+                    .map(|entry| (entry.name.clone(), entry))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut entries = manifest_paths(ctx, &name)?
+            .into_par_iter()
+            .filter_map(|path| {
+                let manifest_name = path.file_stem()?.to_string_lossy().to_string();
+                let modified = modified_millis(&path)?;
+
+                if let Some(entry) = previous.get(&manifest_name) {
+                    if entry.modified == modified {
+                        return Some(entry.clone());
+                    }
+                }
+
+                let manifest = Manifest::from_path(&path).ok()?;
+
+                Some(Entry {
+                    modified,
+                    ..Entry::new(manifest_name, &name, &manifest)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        entries.par_sort_by(|a, b| a.name.cmp(&b.name));
+
+        let index = Self {
+            version: INDEX_VERSION,
+            bucket: name.to_string(),
+            commit: head,
+            entries,
+        };
+
+        index.save(ctx)?;
+
+        Ok(index)
+    }
+
+    /// Find the buckets which contain the given package
+    ///
+    /// Uses each bucket's index if it is fresh, and otherwise falls back to the bucket's manifest file names
+    ///
+    /// # Errors
+    /// - The buckets could not be listed
+    pub fn locate(ctx: &impl ScoopContext, package: &str) -> anyhow::Result<Vec<String>> {
+        let buckets = Bucket::list_all(ctx)?;
+
+        let buckets = buckets
+            .par_iter()
+            .filter(|bucket| match Self::fresh(ctx, bucket) {
+                Some(index) => index
+                    .entries
+                    .iter()
+                    .any(|entry| entry.name.eq_ignore_ascii_case(package)),
+                None => manifest_paths(ctx, &bucket.name()).is_ok_and(|paths| {
+                    paths.iter().any(|path| {
+                        path.file_stem().is_some_and(|stem| {
+                            stem.to_string_lossy().eq_ignore_ascii_case(package)
+                        })
+                    })
+                }),
+            })
+            .map(|bucket| bucket.name().to_string())
+            .collect();
+
+        Ok(buckets)
+    }
+}
+
+/// List the manifest files in a bucket
+///
+/// Manifests live in the `bucket` directory if it exists, otherwise in the bucket root
+fn manifest_paths(ctx: &impl ScoopContext, bucket: &str) -> std::io::Result<Vec<PathBuf>> {
+    let bucket_path = ctx.buckets_path().join(bucket);
+    let nested_path = bucket_path.join("bucket");
+
+    let manifests_path = if nested_path.is_dir() {
+        nested_path
+    } else {
+        bucket_path
+    };
+
+    let paths = std::fs::read_dir(manifests_path)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+
+    Ok(paths)
+}
+
+fn modified_millis(path: &Path) -> Option<u64> {
+    let modified = path.metadata().ok()?.modified().ok()?;
+    let millis = modified.duration_since(UNIX_EPOCH).ok()?.as_millis();
+
+    u64::try_from(millis).ok()
+}
@@ -0,0 +1,437 @@
+//! Lockfiles, pinning each bucket's commit and each app's version, architecture and manifest
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sprinkles::{
+    buckets::Bucket,
+    contexts::ScoopContext,
+    packages::{CreateManifest, InstallManifest, Manifest},
+};
+
+use crate::{errors::Error, models::export::by_name};
+
+/// The version of the lockfile format
+pub const LOCK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A lockfile
+pub struct Lock {
+    /// The version of the lockfile format
+    pub version: u32,
+    /// The locked buckets
+    pub buckets: Vec<LockedBucket>,
+    /// The locked apps
+    pub apps: Vec<LockedApp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A bucket, pinned to a commit
+pub struct LockedBucket {
+    /// The name of the bucket
+    pub name: String,
+    /// The bucket's git remote
+    pub source: String,
+    /// The bucket's HEAD commit
+    pub commit: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An installed app, pinned to its version and manifest
+pub struct LockedApp {
+    /// The name of the app
+    pub name: String,
+    /// The bucket (or url) the app was installed from
+    pub source: String,
+    /// The installed version
+    pub version: String,
+    /// The installed architecture
+    pub architecture: Option<String>,
+    /// The SHA-256 hash of the installed manifest
+    ///
+    /// Broken apps, whose manifest or install manifest could not be read, have no hash
+    pub manifest_hash: Option<String>,
+}
+
+impl LockedBucket {
+    /// Lock a bucket to its current commit
+    ///
+    /// # Errors
+    /// - The bucket's repository could not be opened
+    /// - The bucket's remote or latest commit could not be read
+    pub fn from_bucket(bucket: &Bucket) -> anyhow::Result<Self> {
+        let repo = bucket.open_repo()?;
+
+        Ok(Self {
+            name: bucket.name().to_string(),
+            source: bucket.source()?.to_string(),
+            commit: repo.latest_commit()?.id().to_string(),
+        })
+    }
+}
+
+impl LockedApp {
+    /// Lock an installed app from its directory in the apps directory
+    ///
+    /// Broken apps are still locked, without a manifest hash, so that they are reported as drift
+    ///
+    /// # Errors
+    /// - Invalid file names
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .context("Missing file name")?;
+
+        let current = path.join("current");
+
+        let manifest_path = current.join("manifest.json");
+        let (manifest_hash, manifest) = match (
+            std::fs::read(&manifest_path),
+            Manifest::from_path(&manifest_path),
+        ) {
+            (Ok(contents), Ok(manifest)) => {
+                (Some(format!("{:x}", Sha256::digest(contents))), manifest)
+            }
+            _ => (None, Manifest::default()),
+        };
+        let (manifest_hash, install_manifest) =
+            match InstallManifest::from_path(current.join("install.json")) {
+                Ok(install_manifest) => (manifest_hash, install_manifest),
+                Err(_) => (None, InstallManifest::default()),
+            };
+
+        Ok(Self {
+            name,
+            source: install_manifest.get_source(),
+            version: manifest.version.to_string(),
+            architecture: install_manifest
+                .architecture
+                .map(|architecture| architecture.to_string()),
+            manifest_hash,
+        })
+    }
+}
+
+impl Lock {
+    /// Lock the current state of the buckets and installed apps
+    ///
+    /// Scoop itself is not locked, as it updates itself independently of the buckets.
+    /// Buckets whose repository can't be read are left out, with a warning
+    ///
+    /// # Errors
+    /// - The buckets could not be listed
+    /// - The installed apps could not be listed
+    pub fn load(ctx: &impl ScoopContext) -> anyhow::Result<Self> {
+        let mut buckets = Bucket::list_all(ctx)?
+            .par_iter()
+            // Broken buckets are left out, so that they are reported as missing
+            .filter_map(|bucket| match LockedBucket::from_bucket(bucket) {
+                Ok(locked) => Some(locked),
+                Err(error) => {
+                    warn!("Failed to lock bucket {}: {error:#}", bucket.name());
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        buckets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut apps = ctx
+            .installed_apps()?
+            .par_iter()
+            .filter(|path| {
+                !path
+                    .file_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case("scoop"))
+            })
+            .map(|path| {
+                LockedApp::from_path(path)
+                    .with_context(|| format!("Failed to lock {}", path.display()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        apps.sort_by_key(|app| app.name.to_lowercase());
+
+        Ok(Self {
+            version: LOCK_VERSION,
+            buckets,
+            apps,
+        })
+    }
+
+    /// Read a lockfile
+    ///
+    /// # Errors
+    /// - The file could not be read
+    /// - The file is not a valid lockfile, or is from a newer version of sfsu
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let lock: Self = serde_json::from_slice(&std::fs::read(path)?).map_err(|error| {
+            Error::invalid_input(format!("Invalid lockfile {}: {error}", path.display()))
+        })?;
+
+        if lock.version > LOCK_VERSION {
+            return Err(Error::invalid_input(format!(
+                "Lockfile {} is version {}, but this version of sfsu only supports up to version {LOCK_VERSION}",
+                path.display(),
+                lock.version
+            ))
+            .into());
+        }
+
+        Ok(lock)
+    }
+
+    /// Write the lockfile
+    ///
+    /// # Errors
+    /// - The file could not be written
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+
+        Ok(())
+    }
+
+    #[must_use]
+    /// Compare the actual state against the lock
+    pub fn drift(&self, actual: &Self) -> Vec<Drift> {
+        let mut drift = vec![];
+
+        let actual_buckets = by_name(&actual.buckets, |bucket| &bucket.name);
+        let locked_buckets = by_name(&self.buckets, |bucket| &bucket.name);
+
+        for locked in &self.buckets {
+            let actual = actual_buckets.get(&locked.name.to_lowercase());
+
+            drift.extend(Drift::compare(
+                Item::Bucket,
+                &locked.name,
+                "commit",
+                Some(&locked.commit),
+                actual.map(|actual| &actual.commit),
+            ));
+
+            if let Some(actual) = actual {
+                drift.extend(Drift::compare(
+                    Item::Bucket,
+                    &locked.name,
+                    "source",
+                    Some(&locked.source),
+                    Some(&actual.source),
+                ));
+            }
+        }
+
+        for actual in &actual.buckets {
+            if !locked_buckets.contains_key(&actual.name.to_lowercase()) {
+                drift.extend(Drift::compare(
+                    Item::Bucket,
+                    &actual.name,
+                    "commit",
+                    None,
+                    Some(&actual.commit),
+                ));
+            }
+        }
+
+        let actual_apps = by_name(&actual.apps, |app| &app.name);
+        let locked_apps = by_name(&self.apps, |app| &app.name);
+
+        for locked in &self.apps {
+            let Some(actual) = actual_apps.get(&locked.name.to_lowercase()) else {
+                drift.extend(Drift::compare(
+                    Item::App,
+                    &locked.name,
+                    "version",
+                    Some(&locked.version),
+                    None,
+                ));
+                continue;
+            };
+
+            let fields = [
+                ("source", Some(&locked.source), Some(&actual.source)),
+                ("version", Some(&locked.version), Some(&actual.version)),
+                (
+                    "architecture",
+                    locked.architecture.as_ref(),
+                    actual.architecture.as_ref(),
+                ),
+                (
+                    "manifest_hash",
+                    locked.manifest_hash.as_ref(),
+                    actual.manifest_hash.as_ref(),
+                ),
+            ];
+
+            for (field, locked_value, actual_value) in fields {
+                drift.extend(Drift::compare(
+                    Item::App,
+                    &locked.name,
+                    field,
+                    locked_value,
+                    actual_value,
+                ));
+            }
+        }
+
+        for actual in &actual.apps {
+            if !locked_apps.contains_key(&actual.name.to_lowercase()) {
+                drift.extend(Drift::compare(
+                    Item::App,
+                    &actual.name,
+                    "version",
+                    None,
+                    Some(&actual.version),
+                ));
+            }
+        }
+
+        drift
+    }
+
+    #[must_use]
+    /// The default path for lockfiles
+    pub fn default_path() -> PathBuf {
+        PathBuf::from("sfsu.lock.json")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// The kind of item which drifted
+pub enum Item {
+    Bucket,
+    App,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// A difference between the lock and the actual state
+///
+/// Missing items have no actual value, and items which are not in the lock have no locked value
+pub struct Drift {
+    /// The kind of item which drifted
+    pub item: Item,
+    /// The name of the bucket or app
+    pub name: String,
+    /// The field which drifted
+    pub field: &'static str,
+    /// The locked value
+    pub locked: Option<String>,
+    /// The actual value
+    pub actual: Option<String>,
+}
+
+impl Drift {
+    fn compare(
+        item: Item,
+        name: &str,
+        field: &'static str,
+        locked: Option<&String>,
+        actual: Option<&String>,
+    ) -> Option<Self> {
+        (locked != actual).then(|| Self {
+            item,
+            name: name.to_string(),
+            field,
+            locked: locked.cloned(),
+            actual: actual.cloned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, version: &str, manifest_hash: &str) -> LockedApp {
+        LockedApp {
+            name: name.into(),
+            source: "main".into(),
+            version: version.into(),
+            architecture: Some("64bit".into()),
+            manifest_hash: Some(manifest_hash.into()),
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        let bucket = LockedBucket {
+            name: "main".into(),
+            source: "https://github.com/ScoopInstaller/Main".into(),
+            commit: "abc".into(),
+        };
+
+        let locked = Lock {
+            version: LOCK_VERSION,
+            buckets: vec![bucket.clone()],
+            apps: vec![app("git", "2.45.0", "a1"), app("sfsu", "1.14.0", "b2")],
+        };
+        let actual = Lock {
+            version: LOCK_VERSION,
+            buckets: vec![LockedBucket {
+                commit: "def".into(),
+                ..bucket
+            }],
+            apps: vec![app("git", "2.45.0", "c3"), app("7zip", "24.07", "d4")],
+        };
+
+        assert!(locked.drift(&locked).is_empty());
+
+        let drift = locked.drift(&actual);
+
+        assert_eq!(
+            drift
+                .iter()
+                .map(|drift| (drift.item, drift.name.as_str(), drift.field))
+                .collect::<Vec<_>>(),
+            [
+                (Item::Bucket, "main", "commit"),
+                (Item::App, "git", "manifest_hash"),
+                (Item::App, "sfsu", "version"),
+                (Item::App, "7zip", "version"),
+            ]
+        );
+        assert_eq!(drift[2].actual, None);
+        assert_eq!(drift[3].locked, None);
+    }
+
+    #[test]
+    fn test_drift_broken_app() {
+        let locked = Lock {
+            version: LOCK_VERSION,
+            buckets: vec![],
+            apps: vec![app("git", "2.45.0", "a1")],
+        };
+        let actual = Lock {
+            version: LOCK_VERSION,
+            buckets: vec![],
+            apps: vec![LockedApp {
+                manifest_hash: None,
+                ..app("git", "2.45.0", "a1")
+            }],
+        };
+
+        let drift = locked.drift(&actual);
+
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].field, "manifest_hash");
+        assert_eq!(drift[0].locked.as_deref(), Some("a1"));
+        assert_eq!(drift[0].actual, None);
+    }
+
+    #[test]
+    fn test_from_path_broken_app() {
+        let dir = std::env::temp_dir().join(format!("sfsu-lock-test-{}", std::process::id()));
+        let app_path = dir.join("broken");
+        std::fs::create_dir_all(app_path.join("current")).unwrap();
+
+        let app = LockedApp::from_path(&app_path).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(app.name, "broken");
+        assert_eq!(app.manifest_hash, None);
+    }
+}
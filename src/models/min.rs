@@ -12,6 +12,11 @@ use std::time::UNIX_EPOCH;
 
 use crate::wrappers::time::NicerTime;
 
+/// The note for apps whose install failed
+pub const INSTALL_FAILED: &str = "Install failed";
+/// The note for held apps
+pub const HELD: &str = "Held package";
+
 #[derive(Debug, Serialize)]
 /// Minimal package info
 pub struct Info {
@@ -106,10 +111,10 @@ impl Info {
         let mut notes = vec![];
 
         if broken {
-            notes.push("Install failed".to_string());
+            notes.push(INSTALL_FAILED.to_string());
         }
         if install_manifest.hold.contains_truth() {
-            notes.push("Held package".to_string());
+            notes.push(HELD.to_string());
         }
 
         Ok(Self {
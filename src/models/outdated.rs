@@ -4,6 +4,10 @@ use serde::Serialize;
 
 use sprinkles::packages::Manifest;
 
+use crate::wrappers::{bool::NicerBool, version::Availability};
+
+use super::remote::Remote;
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 /// The outdated info
 pub struct Info {
@@ -13,6 +17,14 @@ pub struct Info {
     pub current: String,
     /// The available version
     pub available: String,
+    /// Whether the available version is an update or a downgrade
+    pub status: Availability,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The bucket the available version was found in, when checking all buckets
+    pub bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether the available version was found in a different bucket than the package was installed from
+    pub bucket_changed: Option<NicerBool>,
 }
 
 impl Info {
@@ -21,14 +33,28 @@ impl Info {
     ///
     /// Returns [`None`] if they have the same version
     pub fn from_manifests(local: &Manifest, remote: &Manifest) -> Option<Self> {
-        if local.version == remote.version {
+        let status = Availability::between(&local.version, &remote.version);
+
+        if status == Availability::UpToDate {
             None
         } else {
             Some(Info {
                 name: unsafe { remote.name() }.to_string(),
                 current: local.version.to_string(),
                 available: remote.version.to_string(),
+                status,
+                bucket: None,
+                bucket_changed: None,
             })
         }
     }
+
+    #[must_use]
+    /// Include the bucket the remote manifest was found in
+    pub fn with_bucket(mut self, remote: &Remote) -> Self {
+        self.bucket = Some(remote.bucket.clone());
+        self.bucket_changed = Some(remote.bucket_changed.into());
+
+        self
+    }
 }
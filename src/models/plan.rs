@@ -0,0 +1,137 @@
+//! The filesystem and git operations a command would perform, printed by `--dry-run`
+
+use std::{fmt::Display, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::output::format::Render;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+/// A single operation which modifies the filesystem or a git repository
+pub enum Operation {
+    /// Remove a file
+    RemoveFile { path: PathBuf },
+    /// Recursively remove a directory
+    RemoveDir { path: PathBuf },
+    /// Write a file, replacing it if it exists
+    WriteFile { path: PathBuf },
+    /// Clone a git repository into a new directory, optionally checking out a branch
+    GitClone {
+        url: String,
+        path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+    },
+    /// Pull the latest changes into a git repository
+    GitPull { path: PathBuf },
+    /// Set a key in the Scoop config
+    SetConfig { key: String, value: String },
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::RemoveFile { path } => write!(f, "remove file {}", path.display()),
+            Operation::RemoveDir { path } => write!(f, "remove directory {}", path.display()),
+            Operation::WriteFile { path } => write!(f, "write file {}", path.display()),
+            Operation::GitClone {
+                url,
+                path,
+                branch: None,
+            } => write!(f, "git clone {url} {}", path.display()),
+            Operation::GitClone {
+                url,
+                path,
+                branch: Some(branch),
+            } => write!(f, "git clone --branch {branch} {url} {}", path.display()),
+            Operation::GitPull { path } => write!(f, "git -C {} pull", path.display()),
+            Operation::SetConfig { key, value } => write!(f, "scoop config {key} {value}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+/// The operations a command would perform, in order
+pub struct Plan(Vec<Operation>);
+
+impl Plan {
+    #[must_use]
+    /// Create an empty plan
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an operation to the plan
+    pub fn push(&mut self, operation: Operation) {
+        self.0.push(operation);
+    }
+
+    #[must_use]
+    /// The operations in the plan
+    pub fn operations(&self) -> &[Operation] {
+        &self.0
+    }
+}
+
+impl FromIterator<Operation> for Plan {
+    fn from_iter<T: IntoIterator<Item = Operation>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Render for Plan {
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            writeln!(f, "Dry run: nothing would be done")?;
+            return Ok(());
+        }
+
+        writeln!(f, "Dry run: the following operations would be performed:")?;
+
+        for operation in &self.0 {
+            writeln!(f, "{}{operation}", crate::output::WHITESPACE)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let plan = [
+            Operation::GitClone {
+                url: "https://github.com/ScoopInstaller/Extras".into(),
+                path: "buckets/extras".into(),
+                branch: None,
+            },
+            Operation::RemoveFile {
+                path: "cache/sfsu#1.0.0#url".into(),
+            },
+        ]
+        .into_iter()
+        .collect::<Plan>();
+
+        let mut output = String::new();
+        plan.render_table(&mut output).unwrap();
+
+        assert_eq!(
+            output,
+            "Dry run: the following operations would be performed:\n  git clone https://github.com/ScoopInstaller/Extras buckets/extras\n  remove file cache/sfsu#1.0.0#url\n"
+        );
+
+        assert_eq!(
+            serde_json::to_value(&plan).unwrap()[0],
+            serde_json::json!({
+                "operation": "git_clone",
+                "url": "https://github.com/ScoopInstaller/Extras",
+                "path": "buckets/extras",
+            })
+        );
+    }
+}
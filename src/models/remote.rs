@@ -0,0 +1,80 @@
+//! Remote manifest lookup
+
+use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::Manifest};
+
+use crate::wrappers::version::Version;
+
+#[derive(Debug, Clone)]
+/// A manifest found in a bucket, to compare against an installed app
+pub struct Remote {
+    /// The remote manifest
+    pub manifest: Manifest,
+    /// The name of the bucket the manifest was found in
+    pub bucket: String,
+    /// Whether the manifest was found in a different bucket than the app was installed from
+    pub bucket_changed: bool,
+}
+
+impl Remote {
+    /// Find the manifest for an installed app
+    ///
+    /// If `buckets` is provided, every bucket is checked and the highest version found is returned,
+    /// otherwise only the bucket the app was installed from is checked.
+    ///
+    /// # Errors
+    /// - The source bucket does not exist
+    /// - The app could not be found in any of the buckets
+    pub fn find(
+        ctx: &impl ScoopContext,
+        buckets: Option<&[Bucket]>,
+        name: &str,
+        source: &str,
+    ) -> anyhow::Result<Self> {
+        if let Some(buckets) = buckets {
+            Self::highest(buckets, name, source)
+        } else {
+            Self::from_source(ctx, name, source)
+        }
+    }
+
+    /// Find the manifest for an app in the bucket it was installed from
+    ///
+    /// # Errors
+    /// - The source bucket does not exist
+    /// - The app could not be found in the source bucket
+    pub fn from_source(ctx: &impl ScoopContext, name: &str, source: &str) -> anyhow::Result<Self> {
+        let bucket = Bucket::from_name(ctx, source)?;
+        let manifest = bucket.get_manifest(name)?;
+
+        Ok(Self {
+            manifest,
+            bucket: bucket.name().to_string(),
+            bucket_changed: false,
+        })
+    }
+
+    /// Find the highest version of an app across all the provided buckets
+    ///
+    /// If multiple buckets provide the same version, the bucket the app was installed from is preferred.
+    ///
+    /// # Errors
+    /// - The app could not be found in any of the buckets
+    pub fn highest(buckets: &[Bucket], name: &str, source: &str) -> anyhow::Result<Self> {
+        let (bucket, manifest) = buckets
+            .iter()
+            .filter_map(|bucket| {
+                bucket
+                    .get_manifest(name)
+                    .ok()
+                    .map(|manifest| (bucket.name().to_string(), manifest))
+            })
+            .max_by_key(|(bucket, manifest)| (Version::new(&manifest.version), bucket == source))
+            .ok_or_else(|| anyhow::anyhow!("{name} could not be found in any bucket"))?;
+
+        Ok(Self {
+            bucket_changed: bucket != source,
+            manifest,
+            bucket,
+        })
+    }
+}
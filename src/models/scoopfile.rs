@@ -0,0 +1,440 @@
+//! Declarative environment files, listing the buckets and apps a machine should have
+//!
+//! Unlike exports, which are a snapshot of a machine, a Scoopfile is the intended state,
+//! and is meant to be committed alongside a project. Scoopfiles may be written in TOML or JSON.
+//!
+//! ```toml
+//! [[buckets]]
+//! name = "extras"
+//! url = "https://github.com/ScoopInstaller/Extras"
+//! branch = "master"
+//!
+//! [[apps]]
+//! name = "extras/sfsu"
+//! version = ">=1.14, <2"
+//! arch = "64bit"
+//! held = true
+//! ```
+
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::models::install};
+
+use crate::{
+    errors::Error,
+    models::{export::same_source, lock::Item},
+    wrappers::version::Version,
+};
+
+/// The files searched for when no Scoopfile is provided, in order
+pub const DEFAULT_PATHS: &[&str] = &["Scoopfile.toml", "Scoopfile.json"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+/// A Scoopfile
+pub struct Scoopfile {
+    /// The required buckets
+    pub buckets: Vec<BucketSpec>,
+    /// The required apps
+    pub apps: Vec<AppSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A required bucket
+pub struct BucketSpec {
+    /// The name of the bucket
+    pub name: String,
+    /// The bucket's git remote. Defaults to the known bucket with the same name
+    pub url: Option<String>,
+    /// The branch the bucket should be on
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A required app
+pub struct AppSpec {
+    /// The name of the app, optionally prefixed with its bucket (i.e `extras/sfsu`)
+    pub name: String,
+    /// The versions the app may be installed at
+    pub version: Option<Constraint>,
+    /// The architecture the app should be installed as
+    pub arch: Option<String>,
+    /// Whether the app should be held
+    pub held: Option<bool>,
+}
+
+impl BucketSpec {
+    #[must_use]
+    /// The bucket's git remote, falling back to the known bucket with the same name
+    pub fn url(&self, ctx: &impl ScoopContext) -> Option<String> {
+        self.url.clone().or_else(|| {
+            ctx.known_buckets()
+                .get(&self.name)
+                .map(|url| (*url).to_string())
+        })
+    }
+}
+
+impl AppSpec {
+    #[must_use]
+    /// The bucket and name of the app
+    pub fn parts(&self) -> (Option<&str>, &str) {
+        match self.name.split_once('/') {
+            Some((bucket, name)) => (Some(bucket), name),
+            None => (None, &self.name),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Operator {
+    fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+/// A version constraint, i.e `1.2.3`, `>=1.2` or `>=1.2, <2`
+///
+/// Versions are compared using Scoop's version ordering. A bare version matches exactly, and `*` matches any version
+pub struct Constraint {
+    raw: String,
+    comparators: Vec<(Operator, String)>,
+}
+
+impl Constraint {
+    #[must_use]
+    /// Check if the version satisfies the constraint
+    pub fn matches(&self, version: &str) -> bool {
+        self.comparators.iter().all(|(operator, required)| {
+            operator.matches(Version::new(version).cmp(&Version::new(required)))
+        })
+    }
+
+    #[must_use]
+    /// Check if a version newer than the given one could satisfy the constraint,
+    /// i.e the version is below every upper bound
+    pub fn allows_newer(&self, version: &str) -> bool {
+        self.comparators
+            .iter()
+            .filter(|(operator, _)| matches!(operator, Operator::Eq | Operator::Lt | Operator::Le))
+            .all(|(_, bound)| Version::new(version) < Version::new(bound))
+    }
+
+    #[must_use]
+    /// The exact version required, if the constraint only allows one
+    pub fn exact(&self) -> Option<&str> {
+        match self.comparators.as_slice() {
+            [(Operator::Eq, version)] => Some(version),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<String> for Constraint {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        let comparators = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|comparator| !comparator.is_empty() && *comparator != "*")
+            .map(|comparator| {
+                let (operator, version) = [
+                    (">=", Operator::Ge),
+                    ("<=", Operator::Le),
+                    (">", Operator::Gt),
+                    ("<", Operator::Lt),
+                    ("=", Operator::Eq),
+                ]
+                .into_iter()
+                .find_map(|(prefix, operator)| {
+                    comparator
+                        .strip_prefix(prefix)
+                        .map(|version| (operator, version.trim()))
+                })
+                .unwrap_or((Operator::Eq, comparator));
+
+                if version.is_empty() {
+                    Err(format!("Missing version in constraint \"{comparator}\""))
+                } else {
+                    Ok((operator, version.to_string()))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { raw, comparators })
+    }
+}
+
+impl From<Constraint> for String {
+    fn from(constraint: Constraint) -> Self {
+        constraint.raw
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// A requirement of the Scoopfile which is not met
+///
+/// Missing buckets and apps have no actual value
+pub struct Unmet {
+    /// The kind of item which does not meet the requirement
+    pub item: Item,
+    /// The name of the bucket or app
+    pub name: String,
+    /// The requirement which is not met
+    pub requirement: &'static str,
+    /// The required value
+    pub expected: String,
+    /// The actual value
+    pub actual: Option<String>,
+}
+
+impl Unmet {
+    fn new(
+        item: Item,
+        name: &str,
+        requirement: &'static str,
+        expected: impl Display,
+        actual: Option<impl Display>,
+    ) -> Self {
+        Self {
+            item,
+            name: name.to_string(),
+            requirement,
+            expected: expected.to_string(),
+            actual: actual.map(|actual| actual.to_string()),
+        }
+    }
+
+    #[must_use]
+    /// Check if the bucket or app is missing entirely
+    pub fn is_missing(&self) -> bool {
+        self.requirement == "installed"
+    }
+}
+
+impl Scoopfile {
+    #[must_use]
+    /// Find the Scoopfile in the current directory
+    pub fn find() -> Option<PathBuf> {
+        DEFAULT_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+    }
+
+    /// Read a Scoopfile, as JSON if it has a `.json` extension, and otherwise as TOML
+    ///
+    /// # Errors
+    /// - The file could not be read
+    /// - The file is not a valid Scoopfile
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+
+        let scoopfile = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            serde_json::from_str(&contents).map_err(|error| error.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|error| error.to_string())
+        };
+
+        scoopfile.map_err(|error| {
+            Error::invalid_input(format!("Invalid Scoopfile {}: {error}", path.display())).into()
+        })
+    }
+
+    /// Check which requirements are not met
+    ///
+    /// # Errors
+    /// - The buckets or installed apps could not be listed
+    pub fn check(&self, ctx: &impl ScoopContext) -> anyhow::Result<Vec<Unmet>> {
+        let mut unmet = vec![];
+
+        let buckets = Bucket::list_all(ctx)?;
+
+        for spec in &self.buckets {
+            let Some(bucket) = buckets.iter().find(|bucket| bucket.name() == spec.name) else {
+                unmet.push(Unmet::new(
+                    Item::Bucket,
+                    &spec.name,
+                    "installed",
+                    spec.url(ctx).unwrap_or_default(),
+                    None::<String>,
+                ));
+                continue;
+            };
+
+            if let Some(ref url) = spec.url {
+                // Buckets with a broken remote have no source, so they never meet the url
+                let source = bucket.source().ok();
+
+                if !source
+                    .as_ref()
+                    .is_some_and(|source| same_source(url, source))
+                {
+                    unmet.push(Unmet::new(Item::Bucket, &spec.name, "url", url, source));
+                }
+            }
+
+            if let Some(ref branch) = spec.branch {
+                let actual = current_branch(&ctx.buckets_path().join(&spec.name));
+
+                if actual.as_ref() != Some(branch) {
+                    unmet.push(Unmet::new(
+                        Item::Bucket,
+                        &spec.name,
+                        "branch",
+                        branch,
+                        actual,
+                    ));
+                }
+            }
+        }
+
+        let apps = install::Manifest::list_all_unchecked(ctx)?;
+
+        for spec in &self.apps {
+            let (bucket, name) = spec.parts();
+
+            let Some(app) = apps
+                .iter()
+                .find(|app| unsafe { app.name() }.eq_ignore_ascii_case(name))
+            else {
+                unmet.push(Unmet::new(
+                    Item::App,
+                    name,
+                    "installed",
+                    &spec.name,
+                    None::<String>,
+                ));
+                continue;
+            };
+
+            if let Some(bucket) = bucket {
+                let source = app.get_source();
+
+                if !source.eq_ignore_ascii_case(bucket) {
+                    unmet.push(Unmet::new(Item::App, name, "bucket", bucket, Some(source)));
+                }
+            }
+
+            if let Some(ref constraint) = spec.version {
+                // Broken apps have no version, so they never meet the constraint
+                let version = app
+                    .get_manifest(ctx)
+                    .ok()
+                    .map(|manifest| manifest.version.to_string());
+
+                if !version
+                    .as_ref()
+                    .is_some_and(|version| constraint.matches(version))
+                {
+                    unmet.push(Unmet::new(Item::App, name, "version", constraint, version));
+                }
+            }
+
+            if let Some(ref arch) = spec.arch {
+                let actual = app.architecture.as_ref().map(ToString::to_string);
+
+                if actual.as_ref() != Some(arch) {
+                    unmet.push(Unmet::new(Item::App, name, "arch", arch, actual));
+                }
+            }
+
+            if let Some(held) = spec.held {
+                let actual = app.hold.unwrap_or_default();
+
+                if actual != held {
+                    unmet.push(Unmet::new(Item::App, name, "held", held, Some(actual)));
+                }
+            }
+        }
+
+        Ok(unmet)
+    }
+}
+
+/// Get the branch a repository is on, from its `HEAD` file
+fn current_branch(repo: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(repo.join(".git").join("HEAD")).ok()?;
+
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints() {
+        let constraint = Constraint::try_from(">=1.14, <2".to_string()).unwrap();
+
+        assert!(constraint.matches("1.14.0"));
+        assert!(constraint.matches("1.15.2"));
+        assert!(!constraint.matches("1.9"));
+        assert!(!constraint.matches("2.0.0"));
+        assert!(constraint.allows_newer("1.9"));
+        assert!(!constraint.allows_newer("2.0.0"));
+        assert_eq!(constraint.exact(), None);
+
+        let exact = Constraint::try_from("1.2.3".to_string()).unwrap();
+        assert_eq!(exact.exact(), Some("1.2.3"));
+        assert!(exact.matches("1.2.3"));
+
+        assert!(Constraint::try_from("*".to_string())
+            .unwrap()
+            .matches("0.1"));
+        assert!(Constraint::try_from(">=".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let scoopfile: Scoopfile = toml::from_str(
+            r#"
+            [[buckets]]
+            name = "extras"
+
+            [[apps]]
+            name = "extras/sfsu"
+            version = ">=1.14"
+            held = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(scoopfile.buckets[0].url, None);
+        assert_eq!(scoopfile.apps[0].parts(), (Some("extras"), "sfsu"));
+        assert_eq!(scoopfile.apps[0].held, Some(true));
+    }
+}
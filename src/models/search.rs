@@ -0,0 +1,210 @@
+//! Search results
+
+use std::{cmp::Ordering, fmt::Display};
+
+use super::index::Entry;
+use crate::output::{
+    sectioned::{Children, Section, Text},
+    theme::Role,
+};
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// A manifest field which can be searched, in addition to the name and binaries
+pub enum Field {
+    Description,
+    Homepage,
+    License,
+    Shortcuts,
+}
+
+impl Field {
+    #[must_use]
+    /// Get the values of this field in the provided index entry
+    pub fn values(self, entry: &Entry) -> Vec<String> {
+        match self {
+            Field::Description => entry.description.iter().cloned().collect(),
+            Field::Homepage => entry.homepage.iter().cloned().collect(),
+            Field::License => entry.license.iter().cloned().collect(),
+            Field::Shortcuts => entry.shortcuts.clone(),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Description => write!(f, "description"),
+            Field::Homepage => write!(f, "homepage"),
+            Field::License => write!(f, "license"),
+            Field::Shortcuts => write!(f, "shortcuts"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// How closely a package matched a search, from most to least relevant
+pub enum Relevance {
+    /// The name is exactly the pattern
+    Exact,
+    /// The name starts with the pattern
+    Prefix,
+    /// The name matches the pattern anywhere else
+    Name,
+    /// One of the package's binaries matches the pattern
+    Binary,
+    /// Another manifest field matches the pattern
+    Field,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A manifest field which matched a search
+pub struct FieldMatch {
+    /// The field which matched
+    pub field: Field,
+    /// The value of the field which matched
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A package which matched a search
+pub struct Match {
+    /// The bucket the package was found in
+    pub bucket: String,
+    /// The name of the package
+    pub name: String,
+    /// The version of the package
+    pub version: String,
+    /// Whether the package is installed
+    pub installed: bool,
+    /// The description of the package
+    pub description: Option<String>,
+    /// The binaries which matched the search
+    pub bins: Vec<String>,
+    /// The other manifest fields which matched the search
+    pub fields: Vec<FieldMatch>,
+    /// How closely the package matched the search
+    pub relevance: Relevance,
+
+    #[serde(skip)]
+    show_installed: bool,
+    #[serde(skip)]
+    show_bucket: bool,
+}
+
+impl Match {
+    #[must_use]
+    /// Create a new match from the provided index entry
+    pub fn new(entry: &Entry, installed: bool, bins: Vec<String>) -> Self {
+        Self {
+            bucket: entry.bucket.clone(),
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            installed,
+            description: entry.description.clone(),
+            bins,
+            fields: vec![],
+            relevance: Relevance::Name,
+            show_installed: true,
+            show_bucket: false,
+        }
+    }
+
+    #[must_use]
+    /// Attach the manifest fields which matched the search
+    pub fn with_fields(mut self, fields: Vec<FieldMatch>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    #[must_use]
+    /// Set how closely the package matched the search
+    pub fn with_relevance(mut self, relevance: Relevance) -> Self {
+        self.relevance = relevance;
+        self
+    }
+
+    #[must_use]
+    /// Show whether the package is installed in text output
+    pub fn with_show_installed(mut self, show_installed: bool) -> Self {
+        self.show_installed = show_installed;
+        self
+    }
+
+    #[must_use]
+    /// Prefix the package name with its bucket in text output, for ungrouped results
+    pub fn with_show_bucket(mut self, show_bucket: bool) -> Self {
+        self.show_bucket = show_bucket;
+        self
+    }
+
+    #[must_use]
+    /// Compare matches by relevance
+    ///
+    /// Ties are broken by installed packages first, then the `main` bucket,
+    /// then the other known buckets, then the name
+    pub fn cmp_relevance(&self, other: &Self, known_buckets: &[String]) -> Ordering {
+        let bucket_priority = |bucket: &str| {
+            if bucket == "main" {
+                0
+            } else if known_buckets.iter().any(|known| known == bucket) {
+                1
+            } else {
+                2
+            }
+        };
+
+        self.relevance
+            .cmp(&other.relevance)
+            .then_with(|| other.installed.cmp(&self.installed))
+            .then_with(|| bucket_priority(&self.bucket).cmp(&bucket_priority(&other.bucket)))
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+impl Display for Match {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = if self.relevance == Relevance::Exact {
+            Role::Highlight.style(&self.name).to_string()
+        } else {
+            self.name.clone()
+        };
+
+        let name = if self.show_bucket {
+            format!("{}/{name}", self.bucket)
+        } else {
+            name
+        };
+
+        let installed = if self.installed && self.show_installed {
+            format!("{} ", Role::Installed.style("[installed]"))
+        } else {
+            String::new()
+        };
+
+        let bins = self.bins.iter().map(|bin| {
+            Text::new(format!(
+                "{}{}",
+                crate::output::WHITESPACE,
+                Role::Highlight.style(bin)
+            ))
+        });
+
+        let fields = self.fields.iter().map(|field_match| {
+            Text::new(format!(
+                "{}{}: {}",
+                crate::output::WHITESPACE,
+                field_match.field,
+                Role::Highlight.style(&field_match.value)
+            ))
+        });
+
+        Section::new(Children::from(bins.chain(fields).collect_vec()))
+            .with_title(format!("{name} ({}) {installed}", self.version))
+            .fmt(f)
+    }
+}
@@ -5,14 +5,14 @@ use quork::traits::truthy::ContainsTruth;
 use serde::Serialize;
 
 use sprinkles::{
-    buckets::Bucket,
     contexts::ScoopContext,
-    packages::{
-        reference::{manifest, package},
-        Manifest, Result,
-    },
+    packages::{reference::package, Manifest, Result},
 };
 
+use crate::wrappers::{bool::NicerBool, version::Availability};
+
+use super::{depends::dependencies, remote::Remote};
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 #[allow(clippy::struct_field_names)]
 /// The status info
@@ -23,6 +23,14 @@ pub struct Info {
     pub current: String,
     /// The available version
     pub available: String,
+    /// Whether the available version is an update or a downgrade
+    pub status: Availability,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The bucket the available version was found in, when checking all buckets
+    pub bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether the available version was found in a different bucket than the package was installed from
+    pub bucket_changed: Option<NicerBool>,
     /// The missing dependencies
     pub missing_dependencies: Vec<package::Reference>,
     /// Additional information
@@ -30,7 +38,7 @@ pub struct Info {
 }
 
 impl Info {
-    /// Parse [`Info`] from a local manifest
+    /// Parse [`Info`] from a local manifest and the matching remote manifest
     ///
     /// # Errors
     /// - If the local manifest is missing
@@ -41,7 +49,7 @@ impl Info {
     pub fn from_manifests(
         ctx: &impl ScoopContext,
         local_manifest: &Manifest,
-        bucket: &Bucket,
+        remote_manifest: &Manifest,
     ) -> Result<Self> {
         let failed = {
             let installed = ctx.app_installed(unsafe { local_manifest.name() })?;
@@ -55,16 +63,13 @@ impl Info {
         };
 
         debug!("Local manifest name: {}", unsafe { local_manifest.name() });
-        let remote_manifest = bucket.get_manifest(unsafe { local_manifest.name() })?;
 
         let install_manifest = local_manifest.install_manifest(ctx)?;
 
         let held = install_manifest.hold.unwrap_or_default();
 
-        let missing_dependencies = local_manifest
-            .depends()
+        let missing_dependencies = dependencies(local_manifest)
             .into_iter()
-            .map(manifest::Reference::into_package_ref)
             .filter(|reference| {
                 debug!(
                     "Checking if {} is installed.",
@@ -87,8 +92,20 @@ impl Info {
             name: unsafe { remote_manifest.name() }.to_string(),
             current: local_manifest.version.to_string(),
             available: remote_manifest.version.to_string(),
+            status: Availability::between(&local_manifest.version, &remote_manifest.version),
+            bucket: None,
+            bucket_changed: None,
             missing_dependencies,
             info: (!info.is_empty()).then_some(info),
         })
     }
+
+    #[must_use]
+    /// Include the bucket the remote manifest was found in
+    pub fn with_bucket(mut self, remote: &Remote) -> Self {
+        self.bucket = Some(remote.bucket.clone());
+        self.bucket_changed = Some(remote.bucket_changed.into());
+
+        self
+    }
 }
@@ -6,8 +6,12 @@
 //!
 pub mod colours;
 pub mod consts;
+pub mod format;
+pub mod layout;
+pub mod pager;
 pub mod sectioned;
 pub mod structured;
+pub mod theme;
 pub mod truncate;
 
 /// Opinionated whitespace for formatting
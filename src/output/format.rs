@@ -0,0 +1,296 @@
+//! Output formats for commands which produce serializable rows
+//!
+//! Rows are serialized to JSON values first, so every format sees the same keys and values
+//! as the JSON output.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::{
+    pager,
+    structured::{cell, flatten, Structured},
+};
+
+/// Data which can be printed in every [`Format`]
+pub trait Render: Serialize {
+    /// Write the human readable output, used by [`Format::Table`]
+    ///
+    /// # Errors
+    /// - The output could not be written
+    fn render_table(&self, f: &mut dyn std::fmt::Write) -> anyhow::Result<()>;
+
+    /// The value printed by row based formats
+    ///
+    /// Defaults to the whole output
+    ///
+    /// # Errors
+    /// - The output could not be serialized
+    /// - The output can't be represented as rows
+    fn rows(&self) -> anyhow::Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+/// The column name used for rows which are not objects (i.e a list of bucket names)
+const VALUE_COLUMN: &str = "value";
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+/// The format to print command output in
+pub enum Format {
+    #[default]
+    /// A human readable table
+    Table,
+    /// Pretty printed JSON
+    Json,
+    /// One compact JSON object per line
+    Ndjson,
+    /// YAML
+    Yaml,
+    /// Comma separated values, with a header row
+    Csv,
+    /// Tab separated values, with a header row
+    Tsv,
+    /// A Markdown table
+    Markdown,
+}
+
+impl Format {
+    #[must_use]
+    /// Resolve the output format from the global `--format` and `--json` flags
+    ///
+    /// `--format` takes precedence, and `--json` is shorthand for `--format json`
+    pub fn resolve(format: Option<Self>, json: bool) -> Self {
+        match format {
+            Some(format) => format,
+            None if json => Self::Json,
+            None => Self::Table,
+        }
+    }
+
+    #[must_use]
+    /// Check if the format is the human readable default
+    pub fn is_table(self) -> bool {
+        self == Self::Table
+    }
+
+    #[must_use]
+    /// Check if the format can represent nested documents, rather than only rows
+    pub fn is_document(self) -> bool {
+        matches!(self, Self::Json | Self::Yaml)
+    }
+
+    /// Print a list of rows in this format
+    ///
+    /// `max_length` is only used by the table format
+    ///
+    /// # Errors
+    /// - The rows could not be serialized
+    /// - Writing to stdout failed
+    pub fn print_rows(
+        self,
+        rows: &[impl Serialize],
+        max_length: Option<usize>,
+    ) -> anyhow::Result<()> {
+        let rows = rows
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match self {
+            Self::Table => {
+                let structured = Structured::new(&rows).with_global_options()?;
+
+                if let Some(max_length) = max_length {
+                    pager::print(structured.with_max_length(max_length))?;
+                } else {
+                    pager::print(structured)?;
+                }
+            }
+            Self::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+            Self::Ndjson => {
+                for row in &rows {
+                    println!("{}", serde_json::to_string(row)?);
+                }
+            }
+            Self::Yaml => print!("{}", serde_yaml::to_string(&rows)?),
+            Self::Csv => write_delimited(&flatten_rows(rows), b',')?,
+            Self::Tsv => write_delimited(&flatten_rows(rows), b'\t')?,
+            Self::Markdown => print!("{}", markdown(&flatten_rows(rows))),
+        }
+
+        Ok(())
+    }
+
+    /// Print the output of a command in this format
+    ///
+    /// # Errors
+    /// - The output could not be rendered or serialized
+    /// - Writing to stdout failed
+    pub fn render(self, output: &impl Render) -> anyhow::Result<()> {
+        if self.is_table() {
+            let mut table = String::new();
+            output.render_table(&mut table)?;
+
+            pager::print(table)?;
+        } else if self.is_document() {
+            self.print_value(output)?;
+        } else {
+            self.print_value(&output.rows()?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print a single value in this format
+    ///
+    /// Arrays are printed as rows. Objects are printed as-is by document formats,
+    /// and as `key`/`value` rows by row based formats.
+    ///
+    /// # Errors
+    /// - The value could not be serialized
+    /// - Writing to stdout failed
+    pub fn print_value(self, value: &impl Serialize) -> anyhow::Result<()> {
+        let value = serde_json::to_value(value)?;
+
+        match (self, value) {
+            (Self::Json, value) => println!("{}", serde_json::to_string_pretty(&value)?),
+            (Self::Yaml, value) => print!("{}", serde_yaml::to_string(&value)?),
+            (Self::Ndjson, value @ Value::Object(_)) => {
+                println!("{}", serde_json::to_string(&value)?);
+            }
+            (_, Value::Array(rows)) => self.print_rows(&rows, None)?,
+            (_, Value::Object(object)) => {
+                let rows = object
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let mut row = Map::new();
+                        row.insert("key".into(), key.into());
+                        row.insert(VALUE_COLUMN.into(), cell(&value).into());
+                        row
+                    })
+                    .collect_vec();
+
+                self.print_rows(&rows, None)?;
+            }
+            (_, value) => self.print_rows(&[value], None)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// List the columns of the provided rows, in the order they first appear
+fn headers(rows: &[Value]) -> Vec<String> {
+    rows.iter()
+        .flat_map(|row| match row {
+            Value::Object(object) => object.keys().cloned().collect_vec(),
+            _ => vec![VALUE_COLUMN.to_string()],
+        })
+        .unique()
+        .collect()
+}
+
+/// Flatten nested objects in each row to dotted columns, as tables do
+fn flatten_rows(rows: Vec<Value>) -> Vec<Value> {
+    rows.into_iter()
+        .map(|row| match row {
+            Value::Object(object) => Value::Object(flatten(object)),
+            value => value,
+        })
+        .collect()
+}
+
+/// Get the cells of a row, in the order of the provided headers
+fn cells(row: &Value, headers: &[String]) -> Vec<String> {
+    match row {
+        Value::Object(object) => headers
+            .iter()
+            .map(|header| object.get(header).map(cell).unwrap_or_default())
+            .collect(),
+        value => headers
+            .iter()
+            .map(|header| {
+                if header == VALUE_COLUMN {
+                    cell(value)
+                } else {
+                    String::new()
+                }
+            })
+            .collect(),
+    }
+}
+
+fn write_delimited(rows: &[Value], delimiter: u8) -> anyhow::Result<()> {
+    let headers = headers(rows);
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(std::io::stdout().lock());
+
+    writer.write_record(&headers)?;
+
+    for row in rows {
+        writer.write_record(cells(row, &headers))?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn markdown(rows: &[Value]) -> String {
+    fn escape(text: &str) -> String {
+        text.replace('|', "\\|").replace('\n', "<br>")
+    }
+
+    let headers = headers(rows);
+
+    let mut output = format!(
+        "| {} |\n|{}|\n",
+        headers.iter().map(|header| escape(header)).join(" | "),
+        headers.iter().map(|_| " --- ").join("|")
+    );
+
+    for row in rows {
+        output.push_str("| ");
+        output.push_str(
+            &cells(row, &headers)
+                .iter()
+                .map(|cell| escape(cell))
+                .join(" | "),
+        );
+        output.push_str(" |\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let rows = [
+            json!({ "name": "sfsu", "version": "1.0|beta" }),
+            json!({ "name": "git", "notes": ["a", "b"] }),
+        ];
+
+        assert_eq!(
+            markdown(&rows),
+            "| name | version | notes |\n| --- | --- | --- |\n| sfsu | 1.0\\|beta |  |\n| git |  | a, b |\n"
+        );
+    }
+
+    #[test]
+    fn test_scalar_rows() {
+        let rows = [json!("main"), json!("extras")];
+
+        assert_eq!(headers(&rows), [VALUE_COLUMN]);
+        assert_eq!(cells(&rows[1], &headers(&rows)), ["extras"]);
+    }
+}
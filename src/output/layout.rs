@@ -0,0 +1,142 @@
+//! Column layout for tables
+//!
+//! Widths are measured in terminal columns rather than bytes,
+//! so multi-byte and wide characters are aligned correctly.
+
+use console::{measure_text_width, Term};
+
+use super::consts::{SUFFIX, WALL};
+
+/// Columns which are always given their full width
+const PRIORITY_COLUMNS: &[&str] = &["name", "version"];
+
+/// The narrowest a column will be shrunk to, unless its content is already narrower
+const MIN_WIDTH: usize = SUFFIX.len() + 5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// How willing a column is to be shrunk, from first to last
+enum Priority {
+    /// Columns containing URLs, which are rarely read in full
+    Url,
+    Normal,
+    /// Columns which are never shrunk
+    Fixed,
+}
+
+impl Priority {
+    fn new(header: &str, cells: impl Iterator<Item = impl AsRef<str>>) -> Self {
+        let header = heck::AsSnakeCase(header).to_string();
+
+        if PRIORITY_COLUMNS.contains(&header.as_str()) {
+            Self::Fixed
+        } else if cells.into_iter().any(|cell| cell.as_ref().contains("://")) {
+            Self::Url
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+#[must_use]
+/// Get the width of the terminal, if stdout is a terminal
+pub fn terminal_width() -> Option<usize> {
+    let term = Term::stdout();
+
+    if !term.is_term() {
+        return None;
+    }
+
+    term.size_checked().map(|(_, columns)| columns.into())
+}
+
+#[must_use]
+/// Calculate the width of each column of a table
+///
+/// Each column starts at the width of its widest cell. Columns other than the priority columns
+/// are then limited to `max_length`, and shrunk until the table fits within `available` columns,
+/// starting with columns containing URLs, and always shrinking the widest column first.
+pub fn column_widths(
+    headers: &[String],
+    rows: &[Vec<String>],
+    max_length: Option<usize>,
+    available: Option<usize>,
+) -> Vec<usize> {
+    let mut columns = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            let cells = rows.iter().filter_map(|row| row.get(i));
+
+            let width = cells
+                .clone()
+                .map(|cell| measure_text_width(cell))
+                .chain(std::iter::once(measure_text_width(header)))
+                .max()
+                .unwrap_or_default();
+
+            (width, Priority::new(header, cells))
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(max_length) = max_length {
+        for (width, priority) in &mut columns {
+            if *priority != Priority::Fixed {
+                *width = (*width).min(max_length.max(MIN_WIDTH));
+            }
+        }
+    }
+
+    if let Some(available) = available {
+        let total = columns
+            .iter()
+            .map(|(width, _)| width + WALL.len())
+            .sum::<usize>();
+        let mut excess = total.saturating_sub(available);
+
+        for shrinking in [Priority::Url, Priority::Normal] {
+            while excess > 0 {
+                let widest = columns
+                    .iter_mut()
+                    .filter(|(width, priority)| *priority == shrinking && *width > MIN_WIDTH)
+                    .max_by_key(|(width, _)| *width);
+
+                let Some((width, _)) = widest else {
+                    break;
+                };
+
+                *width -= 1;
+                excess -= 1;
+            }
+        }
+    }
+
+    columns.into_iter().map(|(width, _)| width).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_widths() {
+        let headers = ["Name", "Version", "Source", "Notes"].map(String::from);
+        let rows = [
+            ["sfsu", "1.0.0", "https://github.com/winpax/sfsu", "añadido"],
+            ["日本語", "2.0", "https://example.com", ""],
+        ]
+        .map(|row| row.map(String::from).to_vec());
+
+        assert_eq!(column_widths(&headers, &rows, None, None), [6, 7, 30, 7]);
+        assert_eq!(
+            column_widths(&headers, &rows, Some(10), None),
+            [6, 7, 10, 7]
+        );
+        // Only the URL column shrinks while it can
+        assert_eq!(
+            column_widths(&headers, &rows, None, Some(50)),
+            [6, 7, 18, 7]
+        );
+        // Priority columns keep their full width
+        assert_eq!(column_widths(&headers, &rows, None, Some(20)), [6, 7, 8, 7]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Paging for long human readable outputs
+
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use console::Term;
+
+static PAGER_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Disable paging for the rest of the program
+pub fn disable() {
+    PAGER_ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Print the output, through a pager if it is taller than the terminal
+///
+/// Outputs are only paged if paging is enabled and stdout is a terminal.
+/// Uses `$PAGER` if it is set, then `less -R` if it is installed, and otherwise a built-in pager.
+///
+/// # Errors
+/// - Writing to the pager failed
+pub fn print(output: impl Display) -> std::io::Result<()> {
+    let output = output.to_string();
+    let term = Term::stdout();
+
+    let fits = term
+        .size_checked()
+        .is_none_or(|(rows, _)| output.lines().count() < rows.into());
+
+    if !PAGER_ENABLED.load(Ordering::Relaxed) || !term.is_term() || fits {
+        print!("{output}");
+        return Ok(());
+    }
+
+    if let Some(command) = external_pager() {
+        match run_external(command, &output) {
+            Ok(()) => return Ok(()),
+            Err(error) => debug!("Failed to run pager, falling back to built-in pager: {error}"),
+        }
+    }
+
+    builtin::page(&output)
+}
+
+fn external_pager() -> Option<Command> {
+    if let Some(pager) = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+    {
+        let mut parts = split_command(&pager).into_iter();
+
+        let mut command = Command::new(parts.next()?);
+        command.args(parts);
+
+        return Some(command);
+    }
+
+    let less = which::which("less").ok()?;
+
+    let mut command = Command::new(less);
+    // Pass through colours
+    command.arg("-R");
+
+    Some(command)
+}
+
+/// Split a command into its arguments, like a shell would
+///
+/// Quoted arguments may contain whitespace, i.e `"C:\Program Files\Git\usr\bin\less.exe" -R`.
+/// Backslashes are kept as is, as they are path separators on Windows
+fn split_command(command: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current: Option<String> = None;
+    let mut quote = None;
+
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                // Empty quotes are still an argument
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => parts.extend(current.take()),
+            (_, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    parts.extend(current);
+    parts
+}
+
+fn run_external(mut command: Command, output: &str) -> std::io::Result<()> {
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input if it is quit before reading everything
+        if let Err(error) = stdin.write_all(output.as_bytes()) {
+            if error.kind() != ErrorKind::BrokenPipe {
+                return Err(error);
+            }
+        }
+    }
+
+    child.wait()?;
+
+    Ok(())
+}
+
+mod builtin {
+    //! A minimal pager, for terminals without one installed
+
+    use std::io::{stdout, Write};
+
+    use crossterm::{
+        cursor::{Hide, MoveTo, Show},
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute, queue,
+        style::{Attribute, Print, SetAttribute},
+        terminal::{
+            self, disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+            LeaveAlternateScreen,
+        },
+    };
+
+    pub fn page(output: &str) -> std::io::Result<()> {
+        let lines = output.lines().collect::<Vec<_>>();
+
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+
+        let result = run(&lines);
+
+        execute!(stdout(), Show, LeaveAlternateScreen)?;
+        disable_raw_mode()?;
+
+        result
+    }
+
+    fn run(lines: &[&str]) -> std::io::Result<()> {
+        let mut top = 0;
+
+        loop {
+            let (columns, rows) = terminal::size()?;
+            // Leave the last row for the status line
+            let page_size = usize::from(rows.saturating_sub(1)).max(1);
+            let last_top = lines.len().saturating_sub(page_size);
+
+            top = top.min(last_top);
+
+            draw(lines, top, page_size, columns.into())?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            top = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Down | KeyCode::Enter | KeyCode::Char('j') => top + 1,
+                KeyCode::Up | KeyCode::Char('k') => top.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ' | 'f') => top + page_size,
+                KeyCode::PageUp | KeyCode::Char('b') => top.saturating_sub(page_size),
+                KeyCode::Home | KeyCode::Char('g') => 0,
+                KeyCode::End | KeyCode::Char('G') => last_top,
+                _ => top,
+            };
+        }
+
+        Ok(())
+    }
+
+    fn draw(lines: &[&str], top: usize, page_size: usize, columns: usize) -> std::io::Result<()> {
+        let mut stdout = stdout();
+
+        queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+
+        for line in lines.iter().skip(top).take(page_size) {
+            // Long lines would wrap and push the rest of the page off screen
+            let line = console::truncate_str(line, columns, "");
+            queue!(stdout, Print(line), Print("\r\n"))?;
+        }
+
+        let bottom = (top + page_size).min(lines.len());
+
+        queue!(
+            stdout,
+            MoveTo(0, u16::try_from(page_size).unwrap_or(u16::MAX)),
+            SetAttribute(Attribute::Reverse),
+            Print(format!(
+                " lines {}-{bottom} of {} (q to quit) ",
+                top + 1,
+                lines.len()
+            )),
+            SetAttribute(Attribute::Reset),
+        )?;
+
+        stdout.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("less -R"), ["less", "-R"]);
+        assert_eq!(
+            split_command(r#""C:\Program Files\Git\usr\bin\less.exe" -R"#),
+            [r"C:\Program Files\Git\usr\bin\less.exe", "-R"]
+        );
+        assert_eq!(
+            split_command("  'my pager'  --prompt=\"a b\" ''"),
+            ["my pager", "--prompt=a b", ""]
+        );
+    }
+}
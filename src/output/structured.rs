@@ -1,6 +1,6 @@
 //! Structured output for the CLI
 
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display, sync::OnceLock};
 
 use itertools::Itertools;
 use serde::Serialize;
@@ -8,7 +8,7 @@ use serde_json::{Map, Value};
 
 use crate::wrappers::header::Header;
 
-use super::{consts::WALL, truncate::FixedLength};
+use super::{consts::WALL, layout, truncate::FixedLength};
 
 pub mod vertical;
 
@@ -30,6 +30,34 @@ fn print_headers(
     Ok(())
 }
 
+static TABLE_OPTIONS: OnceLock<TableOptions> = OnceLock::new();
+
+#[derive(Debug, Clone, Default)]
+/// Options which apply to every [`Structured`] table, set from the global command line flags
+pub struct TableOptions {
+    /// The columns to show, in order. Shows every column if [`None`]
+    pub columns: Option<Vec<String>>,
+    /// Hide the header row
+    pub no_headers: bool,
+    /// The column to sort rows by
+    pub sort_by: Option<String>,
+}
+
+impl TableOptions {
+    /// Set the global table options
+    ///
+    /// Has no effect if the options have already been set
+    pub fn set_global(self) {
+        _ = TABLE_OPTIONS.set(self);
+    }
+
+    #[must_use]
+    /// Get the global table options
+    pub fn global() -> &'static Self {
+        TABLE_OPTIONS.get_or_init(Self::default)
+    }
+}
+
 #[must_use = "Structured is lazy, and only takes effect when used in formatting"]
 /// A table of data
 ///
@@ -37,7 +65,9 @@ fn print_headers(
 /// to be constructed and used within the same function.
 pub struct Structured {
     objects: Vec<Map<String, Value>>,
+    headers: Vec<String>,
     max_length: Option<usize>,
+    no_headers: bool,
 }
 
 impl Structured {
@@ -54,17 +84,103 @@ impl Structured {
 
                 let object = value.as_object().expect("object").clone();
 
-                object
+                flatten(object)
             })
             .collect::<Vec<_>>();
 
+        // Rows may have different columns once flattened, i.e if a nested object is null in some rows
+        let headers = objects
+            .iter()
+            .flat_map(Map::keys)
+            .unique()
+            .cloned()
+            .collect_vec();
+
+        // Drop the column of a nested object which was null in some rows, in favour of its fields
+        let headers = headers
+            .iter()
+            .filter(|header| {
+                let prefix = format!("{header}.");
+                !headers.iter().any(|other| other.starts_with(&prefix))
+            })
+            .cloned()
+            .collect();
+
         Structured {
             objects,
+            headers,
             max_length: None,
+            no_headers: false,
+        }
+    }
+
+    /// Select, order and sort the columns of the table
+    ///
+    /// Column names are matched case-insensitively against the snake case headers.
+    /// Nested columns are named by their path, i.e `license.identifier`
+    ///
+    /// # Errors
+    /// - A provided column does not exist in the table
+    pub fn with_options(mut self, options: &TableOptions) -> anyhow::Result<Self> {
+        self.no_headers = options.no_headers;
+
+        if self.objects.is_empty() {
+            return Ok(self);
+        }
+
+        if let Some(ref sort_by) = options.sort_by {
+            let column = self.find_column(sort_by)?;
+
+            self.objects
+                .sort_by(|a, b| compare_values(a.get(&column), b.get(&column)));
+        }
+
+        if let Some(ref columns) = options.columns {
+            self.headers = columns
+                .iter()
+                .map(|column| self.find_column(column))
+                .collect::<anyhow::Result<_>>()?;
         }
+
+        Ok(self)
+    }
+
+    /// Apply the global [`TableOptions`] to the table
+    ///
+    /// # Errors
+    /// - A provided column does not exist in the table
+    pub fn with_global_options(self) -> anyhow::Result<Self> {
+        self.with_options(TableOptions::global())
+    }
+
+    fn find_column(&self, name: &str) -> anyhow::Result<String> {
+        fn column_name(header: &str) -> String {
+            header
+                .split('.')
+                .map(|segment| heck::AsSnakeCase(segment).to_string())
+                .join(".")
+        }
+
+        let name = column_name(name);
+
+        self.headers
+            .iter()
+            .find(|header| column_name(header) == name)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown column \"{name}\". Valid columns are: {}",
+                    self.headers
+                        .iter()
+                        .map(|header| column_name(header))
+                        .join(", ")
+                )
+            })
     }
 
     /// Add a max length to the [`Structured`] formatter
+    ///
+    /// Limits the width of every column other than the name and version
     pub fn with_max_length(mut self, max: usize) -> Self {
         self.max_length = Some(max);
 
@@ -74,104 +190,38 @@ impl Structured {
 
 impl Display for Structured {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let headers = self.objects[0].keys().collect_vec();
-
-        let contestants = {
-            let default_width = headers
-                .iter()
-                .map(|header| header.len())
-                .max()
-                .unwrap_or(const { "Updated".len() });
+        let headers = self
+            .headers
+            .iter()
+            .map(|header| Header::new(header).to_string())
+            .collect_vec();
 
-            let mut v = vec![default_width];
-            v.extend(headers.iter().map(|s| s.len()));
+        let rows = self
+            .objects
+            .iter()
+            .map(|row| {
+                self.headers
+                    .iter()
+                    .map(|header| row.get(header).map(cell).unwrap_or_default())
+                    .collect_vec()
+            })
+            .collect_vec();
 
-            v
-        };
+        let widths =
+            layout::column_widths(&headers, &rows, self.max_length, layout::terminal_width());
 
-        // TODO: Imeplement max length with truncation
-        let access_lengths: Vec<usize> =
-            self.objects
-                .iter()
-                .fold(vec![0; headers.len()], |base, row| {
-                    // TODO: Simultaneous iterators
+        if !self.no_headers {
+            for (header, width) in headers.iter().zip(&widths) {
+                write!(f, "{:width$}{WALL}", FixedLength::new(header))?;
+            }
 
-                    headers
-                        .iter()
-                        .enumerate()
-                        .map(|(i, header)| {
-                            let element = row
-                                .get(&heck::AsSnakeCase(header).to_string())
-                                .and_then(|v| v.as_str())
-                                .unwrap_or_default();
-
-                            let mut contestants = contestants.clone();
-                            contestants.push(base[i]);
-                            contestants.push(element.len() + WALL.len());
-
-                            contestants.into_iter().max().unwrap()
-                        })
-                        .collect()
-                });
-
-        #[allow(
-            clippy::cast_precision_loss,
-            clippy::cast_possible_truncation,
-            clippy::cast_sign_loss
-        )]
-        let evened_access_lengths = {
-            let term_columns: f64 = console::Term::stdout().size().1.into();
-            let total = access_lengths.iter().sum::<usize>() as f64;
-            let percents = access_lengths.iter().map(|s| ((*s) as f64) / total);
-            let even_parts = percents.map(|p| (p * term_columns).floor() as usize);
-
-            even_parts.collect::<Vec<_>>()
-        };
-
-        let access_lengths = evened_access_lengths;
-
-        for (i, header) in headers.iter().enumerate() {
-            let header_size = access_lengths[i];
-
-            let truncated = FixedLength::new(Header::new(header));
-            write!(f, "{truncated:header_size$}{WALL}")?;
+            // Enter new row
+            writeln!(f)?;
         }
 
-        // Enter new row
-        writeln!(f)?;
-
-        for row in &self.objects {
-            for (i, header) in headers.iter().enumerate() {
-                let value_size = access_lengths[i];
-
-                let element = row
-                    .get(&heck::AsSnakeCase(header).to_string())
-                    .and_then(|v| match v {
-                        Value::Null => None,
-                        Value::Bool(bool) => Some(bool.to_string()),
-                        Value::Number(number) => Some(number.to_string()),
-                        Value::String(string) => Some(string.to_string()),
-                        Value::Array(array) => Some(
-                            array
-                                .iter()
-                                .map(|v| {
-                                    v.as_str()
-                                        .map(std::string::ToString::to_string)
-                                        .unwrap_or_default()
-                                })
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                        ),
-                        Value::Object(_) => panic!("Objects not supported within other objects"),
-                    })
-                    .unwrap_or_default();
-
-                let with_suffix = FixedLength::new(element);
-
-                #[cfg(feature = "v2")]
-                write!(f, "{with_suffix:value_size$}{WALL}")?;
-                #[cfg(not(feature = "v2"))]
-                write!(f, "{with_suffix:value_size$}{WALL}")?;
+        for row in rows {
+            for (element, width) in row.iter().zip(&widths) {
+                write!(f, "{:width$}{WALL}", FixedLength::new(element))?;
             }
 
             // Enter new row
@@ -181,3 +231,140 @@ impl Display for Structured {
         Ok(())
     }
 }
+
+#[must_use]
+/// Flatten nested objects into a single object, joining their keys with dots
+///
+/// i.e `{ "license": { "identifier": "MIT" } }` becomes `{ "license.identifier": "MIT" }`.
+/// Arrays are left as-is.
+pub fn flatten(object: Map<String, Value>) -> Map<String, Value> {
+    fn flatten_into(
+        output: &mut Map<String, Value>,
+        prefix: Option<&str>,
+        object: Map<String, Value>,
+    ) {
+        for (key, value) in object {
+            let key = match prefix {
+                Some(prefix) => format!("{prefix}.{key}"),
+                None => key,
+            };
+
+            match value {
+                Value::Object(object) => flatten_into(output, Some(&key), object),
+                value => {
+                    output.insert(key, value);
+                }
+            }
+        }
+    }
+
+    let mut output = Map::new();
+    flatten_into(&mut output, None, object);
+
+    output
+}
+
+#[must_use]
+/// Convert a value to the text of a single cell
+///
+/// Arrays are joined with commas. Objects, and objects within arrays, are printed as compact JSON
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        Value::Array(array) => array
+            .iter()
+            .map(|value| match value {
+                Value::Array(_) | Value::Object(_) => value.to_string(),
+                value => cell(value),
+            })
+            .join(", "),
+        value => value.to_string(),
+    }
+}
+
+/// Compare two cells, numerically if both are numbers, and as text otherwise
+///
+/// Missing and null cells are sorted last
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (None | Some(Value::Null), None | Some(Value::Null)) => Ordering::Equal,
+        (None | Some(Value::Null), _) => Ordering::Greater,
+        (_, None | Some(Value::Null)) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let text = |value: &Value| match value {
+                Value::String(string) => string.to_lowercase(),
+                value => value.to_string(),
+            };
+
+            text(a).cmp(&text(b))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_table_options() {
+        let values = [
+            json!({ "name": "sfsu", "version": "1.0", "source": "extras" }),
+            json!({ "name": "git", "version": "2.0", "source": "main" }),
+        ];
+
+        let table = Structured::new(&values)
+            .with_options(&TableOptions {
+                columns: Some(vec!["Source".into(), "name".into()]),
+                no_headers: true,
+                sort_by: Some("name".into()),
+            })
+            .unwrap();
+
+        assert_eq!(table.headers, ["source", "name"]);
+        assert_eq!(table.objects[0]["name"], "git");
+
+        let error = Structured::new(&values)
+            .with_options(&TableOptions {
+                columns: Some(vec!["size".into()]),
+                ..Default::default()
+            })
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "Unknown column \"size\". Valid columns are: name, version, source"
+        );
+    }
+
+    #[test]
+    fn test_nested_values() {
+        let values = [
+            json!({ "name": "sfsu", "license": { "identifier": "Apache-2.0", "url": null }, "deps": [1, 2] }),
+            json!({ "name": "git", "license": null, "deps": [{ "name": "7zip" }] }),
+        ];
+
+        assert_eq!(
+            Structured::new(&values).headers,
+            ["name", "license.identifier", "license.url", "deps"]
+        );
+
+        let table = Structured::new(&values)
+            .with_options(&TableOptions {
+                columns: Some(vec!["License.Identifier".into(), "deps".into()]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(table.headers, ["license.identifier", "deps"]);
+        assert_eq!(cell(&table.objects[0]["deps"]), "1, 2");
+        assert_eq!(cell(&table.objects[1]["deps"]), r#"{"name":"7zip"}"#);
+    }
+}
@@ -6,7 +6,9 @@ use itertools::Itertools;
 use serde::Serialize;
 use serde_json::{Map, Value};
 
-use crate::wrappers::header::Header;
+use crate::{output::WHITESPACE, wrappers::header::Header};
+
+use super::cell;
 
 #[must_use = "VTable is lazy, and only takes effect when used in formatting"]
 /// A table of data
@@ -85,25 +87,71 @@ impl Display for VTable {
                 (*header).clone()
             };
 
-            let element = if let Some(element) = element.as_str() {
-                element.to_owned()
-            } else if let Some(array) = element.as_array() {
-                array
-                    .iter()
-                    .map(|v| {
-                        v.as_str()
-                            .map(std::string::ToString::to_string)
-                            .unwrap_or_default()
-                    })
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            } else {
-                element.to_string()
-            };
+            match element {
+                Value::Object(object) => {
+                    writeln!(f, "{header:header_size$} :")?;
+                    self.fmt_nested(f, object)?;
+                }
+                Value::Array(array) if array.iter().any(Value::is_object) => {
+                    writeln!(f, "{header:header_size$} :")?;
+
+                    for (i, element) in array.iter().enumerate() {
+                        if i > 0 {
+                            writeln!(f)?;
+                        }
+
+                        if let Value::Object(object) = element {
+                            self.fmt_nested(f, object)?;
+                        } else {
+                            writeln!(f, "{WHITESPACE}{}", cell(element))?;
+                        }
+                    }
+                }
+                element => writeln!(f, "{header:header_size$} : {}", cell(element))?,
+            }
+        }
 
-            writeln!(f, "{header:header_size$} : {element}")?;
+        Ok(())
+    }
+}
+
+impl VTable {
+    /// Format a nested object as an indented sub-table
+    fn fmt_nested(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        object: &Map<String, Value>,
+    ) -> std::fmt::Result {
+        let nested = Self {
+            object: object.clone(),
+            format_headers: self.format_headers,
+        };
+
+        for line in nested.to_string().lines() {
+            writeln!(f, "{WHITESPACE}{line}")?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_nested_tables() {
+        let table = VTable::new(json!({
+            "name": "sfsu",
+            "license": { "identifier": "Apache-2.0" },
+            "versions": [1, 2],
+        }));
+
+        assert_eq!(
+            table.to_string(),
+            "Name     : sfsu\nLicense  :\n  Identifier : Apache-2.0\nVersions : 1, 2\n"
+        );
+    }
+}
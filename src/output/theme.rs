@@ -0,0 +1,179 @@
+//! Colour themes, mapping the semantic roles of output to styles
+//!
+//! Styles are written in [`console`]'s dotted syntax, i.e `bold.red` or `black.on_yellow`.
+//! Colours are still disabled globally by `--no-color` and `NO_COLOR`.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use console::{Style, StyledObject};
+use serde::{Deserialize, Serialize};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The semantic role of a piece of output
+pub enum Role {
+    /// Errors, and results which need immediate attention
+    Error,
+    /// Warnings, and prompts for potentially destructive actions
+    Warning,
+    /// Successful results
+    Success,
+    /// Text which should stand out, i.e exact search matches
+    Highlight,
+    /// Less important text
+    Muted,
+    /// Installed packages
+    Installed,
+}
+
+impl Role {
+    const ALL: [Self; 6] = [
+        Self::Error,
+        Self::Warning,
+        Self::Success,
+        Self::Highlight,
+        Self::Muted,
+        Self::Installed,
+    ];
+
+    /// Apply the global theme's style for this role to the value
+    pub fn style<D>(self, value: D) -> StyledObject<D> {
+        Theme::global().style(self).apply_to(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// A built-in theme
+pub enum Preset {
+    #[default]
+    /// For terminals with a dark background
+    Dark,
+    /// For terminals with a light background
+    Light,
+    /// Avoids relying on red and green, and uses bold text for every role
+    HighContrast,
+}
+
+impl Preset {
+    fn style(self, role: Role) -> &'static str {
+        match (self, role) {
+            (Self::Dark, Role::Error) => "red",
+            (Self::Dark, Role::Warning) => "yellow",
+            (Self::Dark | Self::Light, Role::Success) | (Self::Dark, Role::Installed) => "green",
+            (Self::Dark, Role::Highlight) => "bold",
+            (Self::Dark | Self::Light, Role::Muted) => "dim",
+
+            (Self::Light, Role::Error) => "red.bold",
+            (Self::Light, Role::Warning) => "magenta",
+            (Self::Light, Role::Highlight) => "bold.blue",
+            (Self::Light, Role::Installed) => "blue",
+
+            (Self::HighContrast, Role::Error) => "bold.underlined.magenta",
+            (Self::HighContrast, Role::Warning) => "bold.yellow",
+            (Self::HighContrast, Role::Success | Role::Installed) => "bold.cyan",
+            (Self::HighContrast, Role::Highlight) => "bold.underlined",
+            (Self::HighContrast, Role::Muted) => "italic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+/// The theme settings, i.e `{ "preset": "light", "styles": { "warning": "bold.yellow" } }`
+pub struct Config {
+    /// The built-in theme to start from
+    pub preset: Preset,
+    /// Styles which override the preset's style for a role
+    pub styles: HashMap<Role, String>,
+}
+
+#[derive(Debug, Clone)]
+/// The styles used for each role
+pub struct Theme {
+    styles: HashMap<Role, Style>,
+}
+
+impl Theme {
+    #[must_use]
+    /// Build a theme from its settings
+    pub fn new(config: &Config) -> Self {
+        let styles = Role::ALL
+            .into_iter()
+            .map(|role| {
+                let style = config
+                    .styles
+                    .get(&role)
+                    .map_or_else(|| config.preset.style(role), String::as_str);
+
+                (role, Style::from_dotted_str(style))
+            })
+            .collect();
+
+        Self { styles }
+    }
+
+    #[must_use]
+    /// Get the style for a role
+    pub fn style(&self, role: Role) -> Style {
+        self.styles.get(&role).cloned().unwrap_or_default()
+    }
+
+    /// Set the global theme
+    ///
+    /// Has no effect if the theme has already been set
+    pub fn set_global(self) {
+        _ = THEME.set(self);
+    }
+
+    #[must_use]
+    /// Get the global theme, or the default theme if it has not been set
+    pub fn global() -> &'static Self {
+        THEME.get_or_init(|| Self::new(&Config::default()))
+    }
+}
+
+/// Create a string styled with the given theme role
+macro_rules! styled {
+    ($role:ident, $($arg:tt)*) => {{
+        $crate::output::theme::Role::$role.style(format!($($arg)*))
+    }};
+}
+
+/// Print a line to stderr styled with the given theme role
+macro_rules! eprintln_styled {
+    ($role:ident, $($arg:tt)*) => {{
+        eprintln!("{}", $crate::output::theme::styled!($role, $($arg)*))
+    }};
+}
+
+pub(crate) use eprintln_styled;
+pub(crate) use styled;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() {
+        let config: Config = serde_json::from_str(
+            r#"{ "preset": "high-contrast", "styles": { "warning": "black.on_yellow" } }"#,
+        )
+        .unwrap();
+
+        let theme = Theme::new(&config);
+
+        console::set_colors_enabled(true);
+
+        assert_eq!(
+            theme.style(Role::Warning).apply_to("!").to_string(),
+            Style::new().black().on_yellow().apply_to("!").to_string()
+        );
+        assert_eq!(
+            theme.style(Role::Success).apply_to("!").to_string(),
+            Style::new().bold().cyan().apply_to("!").to_string()
+        );
+    }
+}
@@ -1,7 +1,13 @@
 use std::fmt::Display;
 
-use super::consts::{SUFFIX, WALL};
+use console::{measure_text_width, pad_str, truncate_str, Alignment};
 
+use super::consts::SUFFIX;
+
+/// Pads or truncates a value to exactly the formatter's width
+///
+/// Widths are measured in terminal columns, so multi-byte and wide characters are never split.
+/// Values which are too long are truncated with [`SUFFIX`].
 pub struct FixedLength<T>(T);
 
 impl<T> FixedLength<T> {
@@ -12,20 +18,34 @@ impl<T> FixedLength<T> {
 
 impl<T: Display> Display for FixedLength<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(width) = f.width() else {
+            return Display::fmt(&self.0, f);
+        };
+
         let data = self.0.to_string();
-        if let Some(length) = f.width() {
-            let length = length - WALL.len();
-            if data.len() > length {
-                write!(
-                    f,
-                    "{}{SUFFIX}",
-                    &data[0..length.checked_sub(3).unwrap_or_default()]
-                )
-            } else {
-                write!(f, "{data:length$}")
-            }
+
+        let truncated = if measure_text_width(&data) <= width {
+            data.into()
+        } else if width <= SUFFIX.len() {
+            // Too narrow to fit the suffix, so just cut the value off
+            truncate_str(&data, width, "")
         } else {
-            Display::fmt(&self.0, f)
-        }
+            truncate_str(&data, width, SUFFIX)
+        };
+
+        f.write_str(&pad_str(&truncated, width, Alignment::Left, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_truncation() {
+        assert_eq!(format!("{:6}", FixedLength::new("añb")), "añb   ");
+        assert_eq!(format!("{:6}", FixedLength::new("ñññññññ")), "ñññ...");
+        assert_eq!(format!("{:5}", FixedLength::new("日本語の名前")), "日...");
+        assert_eq!(format!("{:2}", FixedLength::new("sfsu")), "sf");
     }
 }
@@ -0,0 +1,297 @@
+//! Pattern matching shared by commands which take package name patterns
+//!
+//! Patterns are globs by default (i.e `python*`, `7zip?`), and can be opted into full regex
+//! syntax with `--regex`. Matching is case-insensitive unless `--case-sensitive` is passed.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::errors::Error;
+
+/// Characters which make a pattern a glob, rather than a literal name
+const GLOB_CHARS: &[char] = &['*', '?', '['];
+
+/// Characters with a special meaning in regex syntax
+const REGEX_CHARS: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$',
+];
+
+#[derive(Debug, Copy, Clone, Default)]
+#[must_use]
+/// Options for compiling a [`Pattern`]
+pub struct Options {
+    regex: bool,
+    case_sensitive: bool,
+    partial: bool,
+}
+
+impl Options {
+    /// Create the default options: a case-insensitive glob matching the whole string
+    pub const fn new() -> Self {
+        Self {
+            regex: false,
+            case_sensitive: false,
+            partial: false,
+        }
+    }
+
+    /// Treat the pattern as a regex rather than a glob
+    pub const fn regex(mut self, regex: bool) -> Self {
+        self.regex = regex;
+        self
+    }
+
+    /// Match case-sensitively
+    pub const fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Allow the pattern to match part of the string
+    ///
+    /// Regexes are left unanchored, and globs without any wildcards match as a substring.
+    /// Globs with wildcards always match the whole string, so `python*` means "starts with python".
+    pub const fn partial(mut self, partial: bool) -> Self {
+        self.partial = partial;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A compiled glob or regex pattern
+pub struct Pattern {
+    source: String,
+    is_regex: bool,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Compile the provided pattern
+    ///
+    /// # Errors
+    /// - The pattern is not a valid regex (with [`Options::regex`]), or glob
+    pub fn new(pattern: &str, options: Options) -> Result<Self, Error> {
+        let expression = if options.regex {
+            if options.partial {
+                pattern.to_string()
+            } else {
+                format!("^(?:{pattern})$")
+            }
+        } else if options.partial && !is_glob(pattern) {
+            regex::escape(pattern)
+        } else {
+            format!("^{}$", glob_to_regex(pattern))
+        };
+
+        let regex = RegexBuilder::new(&expression)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|error| {
+                if options.regex {
+                    Error::invalid_input(format!(
+                        "Invalid regex \"{pattern}\": {error}. See https://docs.rs/regex/latest/regex/ for more info"
+                    ))
+                } else {
+                    // Globs are escaped, so only character classes can be invalid
+                    Error::invalid_input(format!(
+                        "Invalid glob \"{pattern}\". Character ranges must be in order, i.e `[a-z]` rather than `[z-a]`"
+                    ))
+                }
+            })?;
+
+        Ok(Self {
+            source: pattern.to_string(),
+            is_regex: options.regex,
+            regex,
+        })
+    }
+
+    #[must_use]
+    /// Check if the pattern matches the provided text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    #[must_use]
+    /// Check if the text is exactly the pattern, ignoring case
+    pub fn is_exact(&self, text: &str) -> bool {
+        self.source.eq_ignore_ascii_case(text)
+    }
+
+    #[must_use]
+    /// Check if the text starts with the literal part of the pattern, ignoring case
+    ///
+    /// The literal part is everything before the first wildcard (or regex syntax),
+    /// so `python*` is a prefix of `python311`
+    pub fn is_prefix_of(&self, text: &str) -> bool {
+        let prefix = if self.is_regex {
+            let source = self.source.trim_start_matches('^');
+            source.split(REGEX_CHARS).next().unwrap_or_default()
+        } else {
+            self.source.split(GLOB_CHARS).next().unwrap_or_default()
+        };
+
+        !prefix.is_empty() && text.to_lowercase().starts_with(&prefix.to_lowercase())
+    }
+
+    #[must_use]
+    /// Get the compiled regex, for APIs which require one
+    pub fn as_regex(&self) -> &Regex {
+        &self.regex
+    }
+}
+
+#[must_use]
+/// Check if the provided pattern contains any glob wildcards
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(GLOB_CHARS)
+}
+
+/// Convert a glob to the equivalent (unanchored) regex
+fn glob_to_regex(glob: &str) -> String {
+    let mut output = String::new();
+    let mut chars = glob.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => output.push_str(".*"),
+            '?' => output.push('.'),
+            '[' => {
+                let rest = chars.as_str();
+
+                // An unterminated class is matched literally
+                if let Some((class, length)) = class_to_regex(rest) {
+                    output.push_str(&class);
+                    chars = rest[length..].chars();
+                } else {
+                    output.push_str(r"\[");
+                }
+            }
+            c => output.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    output
+}
+
+/// Convert a glob character class to the equivalent regex class
+///
+/// Takes the glob following the opening `[`, and returns the class and the length of the glob it used,
+/// or `None` if the class is never closed. As in shell globs, `!` negates the class,
+/// and a `]` straight after the opening bracket is part of the class
+fn class_to_regex(glob: &str) -> Option<(String, usize)> {
+    let negated = glob.starts_with('!');
+    let start = usize::from(negated);
+
+    let end = start
+        + glob[start..]
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == ']')?
+            .0;
+
+    let members = glob[start..end].chars().collect::<Vec<_>>();
+
+    let mut class = String::from(if negated { "[^" } else { "[" });
+
+    for (i, &c) in members.iter().enumerate() {
+        // A dash between two other characters is a range, and anything else is literal
+        let is_range = c == '-'
+            && i > 0
+            && members.get(i + 1).is_some_and(|&next| next != '-')
+            && members[i - 1] != '-';
+
+        if is_range {
+            class.push('-');
+        } else {
+            class.push_str(&regex::escape(&c.to_string()));
+        }
+    }
+
+    class.push(']');
+
+    Some((class, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str) -> Pattern {
+        Pattern::new(pattern, Options::new()).unwrap()
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(glob("python*").is_match("python"));
+        assert!(glob("python*").is_match("python311"));
+        assert!(!glob("python*").is_match("ipython"));
+        assert!(glob("7zip?").is_match("7zip1"));
+        assert!(!glob("7zip?").is_match("7zip"));
+        assert!(glob("app[0-9]").is_match("app3"));
+        assert!(!glob("app[!0-9]").is_match("app3"));
+        assert!(glob("vcredist.2022").is_match("vcredist.2022"));
+        assert!(!glob("vcredist.2022").is_match("vcredist-2022"));
+    }
+
+    #[test]
+    fn test_glob_classes() {
+        // Unterminated and empty classes are literal
+        assert!(glob("[").is_match("["));
+        assert!(glob("[]").is_match("[]"));
+        assert!(glob("app[").is_match("app["));
+        assert!(glob("[!]").is_match("[!]"));
+
+        // A leading `]` is part of the class
+        assert!(glob("[]a]").is_match("]"));
+        assert!(glob("[]a]").is_match("a"));
+
+        // Regex syntax inside a class is literal
+        assert!(glob("[\\d]").is_match("\\"));
+        assert!(!glob("[\\d]").is_match("1"));
+        assert!(glob("[a&&b]").is_match("&"));
+        assert!(glob("[[:x]").is_match(":"));
+        assert!(glob("[-a]").is_match("-"));
+        assert!(glob("[a-]").is_match("-"));
+        assert!(!glob("[a-c]").is_match("-"));
+
+        let error = Pattern::new("[z-a]", Options::new()).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid glob"));
+    }
+
+    #[test]
+    fn test_case_sensitivity() {
+        assert!(glob("Python*").is_match("python"));
+
+        let pattern = Pattern::new("Python*", Options::new().case_sensitive(true)).unwrap();
+        assert!(!pattern.is_match("python"));
+    }
+
+    #[test]
+    fn test_partial_matching() {
+        let partial = |pattern| Pattern::new(pattern, Options::new().partial(true)).unwrap();
+
+        assert!(partial("git").is_match("lazygit"));
+        assert!(!glob("git").is_match("lazygit"));
+        assert!(!partial("git*").is_match("lazygit"));
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert!(glob("python*").is_prefix_of("Python311"));
+        assert!(!glob("*python").is_prefix_of("python"));
+
+        let regex = Pattern::new("^py.*n$", Options::new().regex(true)).unwrap();
+        assert!(regex.is_prefix_of("python"));
+        assert!(!regex.is_prefix_of("ipython"));
+    }
+
+    #[test]
+    fn test_regex_matching() {
+        let regex = Pattern::new("^py.*n$", Options::new().regex(true).partial(true)).unwrap();
+        assert!(regex.is_match("python"));
+
+        let anchored = Pattern::new("py|rb", Options::new().regex(true)).unwrap();
+        assert!(anchored.is_match("py"));
+        assert!(!anchored.is_match("pypy"));
+    }
+}
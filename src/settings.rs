@@ -0,0 +1,54 @@
+//! sfsu's own settings
+//!
+//! Settings are stored alongside sfsu's other persisted data, in `persist/sfsu/settings.json`.
+//! Missing keys use their default values.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sprinkles::contexts::ScoopContext;
+
+use crate::output::theme;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+/// sfsu's settings
+pub struct Settings {
+    /// Page long human readable outputs when printing to a terminal
+    pub pager: bool,
+    /// The colour theme, and any overrides to it
+    pub theme: theme::Config,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            pager: true,
+            theme: theme::Config::default(),
+        }
+    }
+}
+
+impl Settings {
+    #[must_use]
+    /// The path to the settings file
+    pub fn path(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.persist_path().join("sfsu").join("settings.json")
+    }
+
+    /// Load the settings, falling back to the defaults if the file is missing
+    ///
+    /// # Errors
+    /// - The settings file is invalid
+    pub fn load(ctx: &impl ScoopContext) -> anyhow::Result<Self> {
+        let path = Self::path(ctx);
+
+        let Ok(settings) = std::fs::read(&path) else {
+            return Ok(Self::default());
+        };
+
+        serde_json::from_slice(&settings)
+            .with_context(|| format!("Invalid settings file at {}", path.display()))
+    }
+}
@@ -6,3 +6,4 @@ pub mod header;
 pub mod serialize;
 pub mod sizes;
 pub mod time;
+pub mod version;
@@ -5,7 +5,7 @@ use std::fmt::Display;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use serde::{de::Visitor, Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, AsRef, AsMut, Deref, DerefMut)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, AsRef, AsMut, Deref, DerefMut)]
 /// A nicer way to display booleans
 pub struct NicerBool(bool);
 
@@ -0,0 +1,299 @@
+//! Scoop-compatible version ordering
+//!
+//! Mirrors the rules of Scoop's `Compare-Version` function, so that versions like `1.2.3.4`,
+//! `nightly-20240101` and `2.0-beta3` can be ordered, which `semver` cannot do.
+
+use std::{cmp::Ordering, fmt::Display};
+
+use serde::Serialize;
+
+use super::serialize::SerializeDisplay;
+
+/// Tags which mark a version as a pre-release, and thus older than the release itself
+const PRE_RELEASE_TAGS: &[&str] = &["alpha", "beta", "rc", "pre", "preview", "dev"];
+
+#[derive(Debug, Clone)]
+enum Part {
+    Number(String),
+    Text(String),
+}
+
+impl Part {
+    /// The part missing versions are padded with, so that `1.0` and `1.0.0` are equal
+    fn zero() -> Self {
+        Part::Number(String::new())
+    }
+
+    fn is_pre_release(&self) -> bool {
+        match self {
+            Part::Number(_) => false,
+            Part::Text(text) => PRE_RELEASE_TAGS.iter().any(|tag| text.starts_with(tag)),
+        }
+    }
+
+    /// Parts are first ordered by class: pre-release tags, then numbers, then any other text
+    ///
+    /// i.e `1.0-beta` < `1.0` < `1.0.1` < `1.0a`
+    fn class(&self) -> u8 {
+        match self {
+            text @ Part::Text(_) if text.is_pre_release() => 0,
+            Part::Number(_) => 1,
+            Part::Text(_) => 2,
+        }
+    }
+}
+
+impl Ord for Part {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.class()
+            .cmp(&other.class())
+            .then_with(|| match (self, other) {
+                (Part::Number(a), Part::Number(b)) => {
+                    // Compare by length first, so that we never overflow on absurdly long numbers
+                    let a = a.trim_start_matches('0');
+                    let b = b.trim_start_matches('0');
+
+                    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+                }
+                (Part::Text(a), Part::Text(b)) => a.cmp(b),
+                // Parts of the same class are always the same variant
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for Part {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Part {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Part {}
+
+fn split(version: &str) -> Vec<Part> {
+    let mut parts = vec![];
+    let mut current = String::new();
+
+    let mut push = |current: &mut String| {
+        if current.is_empty() {
+            return;
+        }
+
+        let part = std::mem::take(current);
+        if part.chars().all(|c| c.is_ascii_digit()) {
+            parts.push(Part::Number(part));
+        } else {
+            parts.push(Part::Text(part));
+        }
+    };
+
+    for c in version.chars() {
+        if matches!(c, '.' | '-' | '_') {
+            push(&mut current);
+            continue;
+        }
+
+        // Split on boundaries between numbers and letters, i.e `beta3` -> `beta`, `3`
+        if let Some(last) = current.chars().last() {
+            if last.is_ascii_digit() != c.is_ascii_digit() {
+                push(&mut current);
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    push(&mut current);
+
+    parts
+}
+
+#[derive(Debug, Clone)]
+/// A version which is ordered according to Scoop's rules
+pub struct Version(String);
+
+impl Version {
+    #[must_use]
+    /// Create a new [`Version`] from the provided version string
+    pub fn new(version: impl Display) -> Self {
+        Self(version.to_string())
+    }
+
+    /// Strip build metadata (i.e `+b25`), which is ignored for ordering
+    fn trimmed(&self) -> &str {
+        self.0.split('+').next().unwrap_or_default()
+    }
+
+    fn is_nightly(&self) -> bool {
+        self.trimmed().to_lowercase().starts_with("nightly")
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.trimmed(), other.trimmed());
+
+        if a.eq_ignore_ascii_case(b) {
+            return Ordering::Equal;
+        }
+
+        // Nightly versions are always considered the newest
+        match (self.is_nightly(), other.is_nightly()) {
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            _ => {}
+        }
+
+        let (a, b) = (split(a), split(b));
+        let zero = Part::zero();
+
+        // Missing parts compare as zero, so `1.1-beta` is older than `1.1`, but `1.1.1` is newer
+        (0..a.len().max(b.len()))
+            .map(|i| {
+                let a = a.get(i).unwrap_or(&zero);
+                let b = b.get(i).unwrap_or(&zero);
+
+                a.cmp(b)
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// The availability of a different version of a package
+pub enum Availability {
+    /// The installed version is the same as the available version
+    UpToDate,
+    /// A newer version is available
+    Update,
+    /// The installed version is newer than the available version
+    Downgrade,
+}
+
+impl Availability {
+    #[must_use]
+    /// Compare the installed version against the available version
+    pub fn between(current: impl Display, available: impl Display) -> Self {
+        match Version::new(current).cmp(&Version::new(available)) {
+            Ordering::Equal => Self::UpToDate,
+            Ordering::Less => Self::Update,
+            Ordering::Greater => Self::Downgrade,
+        }
+    }
+}
+
+impl Display for Availability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Availability::UpToDate => write!(f, "Up to date"),
+            Availability::Update => write!(f, "Update available"),
+            Availability::Downgrade => write!(f, "Downgrade available"),
+        }
+    }
+}
+
+impl Serialize for Availability {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeDisplay::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_newer(newer: &str, older: &str) {
+        assert!(
+            Version::new(newer) > Version::new(older),
+            "expected {newer} to be newer than {older}"
+        );
+        assert!(Version::new(older) < Version::new(newer));
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        assert_newer("1.2.3.5", "1.2.3.4");
+        assert_newer("1.10", "1.9");
+        assert_newer("1.2.3.4", "1.2.3");
+        assert_newer("2.0", "2.0-beta3");
+        assert_newer("2.0-beta3", "2.0-beta2");
+        assert_newer("2.0-rc1", "2.0-beta3");
+        assert_newer("2.0.1", "2.0-beta3");
+        assert_newer("1.0_2", "1.0_1");
+        assert_newer("nightly-20240101", "99.0");
+        assert_newer("nightly-20240102", "nightly-20240101");
+        assert_newer("3.0", "2.12.0");
+
+        assert_eq!(Version::new("1.0.0"), Version::new("1.0.0"));
+        assert_eq!(Version::new("1.0.0+b25"), Version::new("1.0.0+ea"));
+        assert_eq!(Version::new("01.2"), Version::new("1.2"));
+        assert_newer("1.3", "01.2");
+
+        assert_eq!(Version::new("1.0"), Version::new("1.0.0"));
+        assert_eq!(Version::new("1.0"), Version::new("1.0.0.0"));
+        assert_newer("1.0", "1.0.0-beta");
+        assert_newer("1.0a", "1.0.1");
+    }
+
+    #[test]
+    fn test_mixed_parts() {
+        // Pre-release tags sort before numbers, and any other text sorts after them
+        assert_newer("1.1", "1.alpha");
+        assert_newer("1.a", "1.1");
+        assert_newer("1.a", "1.alpha");
+
+        let versions = [
+            "1.alpha", "1.a", "1.1", "1", "1.0.0", "1.beta2", "1.rc", "1-dev", "1.0a", "2", "0.9",
+        ]
+        .map(Version::new);
+
+        // The ordering must be transitive, or sorting gives arbitrary results
+        for a in &versions {
+            for b in &versions {
+                for c in &versions {
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{a} <= {b} <= {c}, but {a} > {c}");
+                    }
+                }
+            }
+        }
+
+        let mut sorted = versions.to_vec();
+        sorted.sort();
+        assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_availability() {
+        assert_eq!(Availability::between("1.0", "1.0"), Availability::UpToDate);
+        assert_eq!(Availability::between("1.0", "1.1"), Availability::Update);
+        assert_eq!(Availability::between("1.1", "1.0"), Availability::Downgrade);
+    }
+}